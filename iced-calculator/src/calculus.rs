@@ -0,0 +1,397 @@
+use crate::expr::{self, Context, Expr};
+use std::fmt;
+
+/// The variable `diff` and `integral` bind in their first argument.
+pub const VARIABLE: &str = "x";
+
+const TOLERANCE: f64 = 1e-10;
+const MAX_SUBINTERVALS: usize = 500;
+/// Halvings of an end segment in a row that leave its error estimate no smaller before
+/// the integral counts as divergent. An integrable singularity such as `1/√x` shrinks
+/// the error at every halving, while `1/x` keeps it exactly the same.
+const STAGNANT_HALVINGS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NotFinite(f64),
+    Divergent { estimate: f64, error: f64 },
+    NoConvergence { estimate: f64, error: f64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFinite(x) => write!(f, "integrand is not finite at {VARIABLE} = {x}"),
+            Error::Divergent { estimate, error } => write!(
+                f,
+                "integral diverges (estimate {estimate}, error ≈ {error:.1e})"
+            ),
+            Error::NoConvergence { estimate, error } => write!(
+                f,
+                "integral did not converge (estimate {estimate}, error ≈ {error:.1e})"
+            ),
+        }
+    }
+}
+
+/// A numeric result together with its estimated absolute error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+    pub error: f64,
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (error ≈ {:.1e})", self.value, self.error)
+    }
+}
+
+/// Whether `name` is a calculus operator whose first argument must stay unevaluated.
+pub fn is_operator(name: &str) -> bool {
+    matches!(name, "diff" | "integral")
+}
+
+/// Evaluates `diff(f, x0)` or `integral(f, a, b)`, binding [`VARIABLE`] inside `f`.
+///
+/// The parser also reads `d/dx f at x0` and `∫ f dx from a to b` as these calls.
+pub fn call(name: &str, args: &[Expr], context: &Context) -> Result<f64, expr::Error> {
+    let expected = if name == "diff" { 2 } else { 3 };
+    let [body, points @ ..] = args else {
+        return Err(expr::Error::WrongArgumentCount {
+            name: name.to_string(),
+            expected,
+            found: 0,
+        });
+    };
+    if args.len() != expected {
+        return Err(expr::Error::WrongArgumentCount {
+            name: name.to_string(),
+            expected,
+            found: args.len(),
+        });
+    }
+
+    let points = points
+        .iter()
+        .map(|point| point.eval_number(context))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut scope = context.clone();
+    let mut f = |x: f64| {
+        scope.set(VARIABLE, x);
+        body.eval_number(&scope)
+    };
+
+    let estimate = if name == "diff" {
+        derivative(&mut f, points[0])?
+    } else {
+        integral(&mut f, points[0], points[1])?
+    };
+    context.note(format!("{name}: {estimate}"));
+    Ok(estimate.value)
+}
+
+/// Ridders' method: central differences with Richardson extrapolation.
+fn derivative(
+    f: &mut impl FnMut(f64) -> Result<f64, expr::Error>,
+    x: f64,
+) -> Result<Estimate, expr::Error> {
+    const STEPS: usize = 10;
+    const SHRINK: f64 = 1.4;
+
+    let mut h = 0.1 * x.abs().max(1.0);
+    let mut table = [[0.0; STEPS]; STEPS];
+    let mut best = Estimate {
+        value: f64::NAN,
+        error: f64::INFINITY,
+    };
+
+    for i in 0..STEPS {
+        table[0][i] = (f(x + h)? - f(x - h)?) / (2.0 * h);
+        let mut factor = SHRINK * SHRINK;
+        for j in 1..=i {
+            table[j][i] = (table[j - 1][i] * factor - table[j - 1][i - 1]) / (factor - 1.0);
+            factor *= SHRINK * SHRINK;
+            let error = (table[j][i] - table[j - 1][i])
+                .abs()
+                .max((table[j][i] - table[j - 1][i - 1]).abs());
+            if error <= best.error {
+                best = Estimate {
+                    value: table[j][i],
+                    error,
+                };
+            }
+        }
+        // Stop once higher orders make things worse rather than better.
+        if i > 0 && (table[i][i] - table[i - 1][i - 1]).abs() >= 2.0 * best.error {
+            break;
+        }
+        h /= SHRINK;
+    }
+
+    if best.value.is_finite() {
+        Ok(best)
+    } else {
+        Err(expr::Error::Domain(format!(
+            "derivative is not finite at {VARIABLE} = {x}"
+        )))
+    }
+}
+
+// Gauss–Kronrod 7–15 nodes and weights on [-1, 1].
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_2,
+    0.063_092_092_629_978_6,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+// Gauss weights for the odd-indexed Kronrod nodes.
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+struct Segment {
+    a: f64,
+    b: f64,
+    value: f64,
+    error: f64,
+}
+
+fn gauss_kronrod(
+    f: &mut impl FnMut(f64) -> Result<f64, Error>,
+    a: f64,
+    b: f64,
+) -> Result<Segment, Error> {
+    let center = 0.5 * (a + b);
+    let half = 0.5 * (b - a);
+    let mut kronrod = 0.0;
+    let mut gauss = 0.0;
+
+    for (i, (&node, &weight)) in KRONROD_NODES.iter().zip(&KRONROD_WEIGHTS).enumerate() {
+        let values = if node == 0.0 {
+            let y = f(center)?;
+            [y, 0.0]
+        } else {
+            [f(center - half * node)?, f(center + half * node)?]
+        };
+        let sum = values[0] + values[1];
+        kronrod += weight * sum;
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * sum;
+        }
+    }
+
+    Ok(Segment {
+        a,
+        b,
+        value: kronrod * half,
+        error: ((kronrod - gauss) * half).abs(),
+    })
+}
+
+/// Adaptive Gauss–Kronrod quadrature, always splitting the worst segment.
+///
+/// Infinite bounds are mapped onto a finite interval first.
+fn integral(
+    f: &mut impl FnMut(f64) -> Result<f64, expr::Error>,
+    a: f64,
+    b: f64,
+) -> Result<Estimate, expr::Error> {
+    if a == b {
+        return Ok(Estimate {
+            value: 0.0,
+            error: 0.0,
+        });
+    }
+    if a > b {
+        let estimate = integral(f, b, a)?;
+        return Ok(Estimate {
+            value: -estimate.value,
+            ..estimate
+        });
+    }
+
+    let mut expression_error = None;
+    let mut g = |t: f64| -> Result<f64, Error> {
+        let (x, jacobian) = match (a.is_finite(), b.is_finite()) {
+            (true, true) => (t, 1.0),
+            (true, false) => (a + t / (1.0 - t), 1.0 / ((1.0 - t) * (1.0 - t))),
+            (false, true) => (b - (1.0 - t) / t, 1.0 / (t * t)),
+            (false, false) => (t / (1.0 - t * t), (1.0 + t * t) / (1.0 - t * t).powi(2)),
+        };
+        match f(x) {
+            Ok(y) if (y * jacobian).is_finite() => Ok(y * jacobian),
+            Ok(_) => Err(Error::NotFinite(x)),
+            Err(error) => {
+                expression_error = Some(error);
+                Err(Error::NotFinite(x))
+            }
+        }
+    };
+    let (start, end) = match (a.is_finite(), b.is_finite()) {
+        (true, true) => (a, b),
+        (true, false) => (0.0, 1.0),
+        (false, true) => (0.0, 1.0),
+        (false, false) => (-1.0, 1.0),
+    };
+
+    let result = adaptive(&mut g, start, end);
+    if let Some(error) = expression_error {
+        return Err(error);
+    }
+    result.map_err(expr::Error::from)
+}
+
+fn adaptive(
+    f: &mut impl FnMut(f64) -> Result<f64, Error>,
+    a: f64,
+    b: f64,
+) -> Result<Estimate, Error> {
+    let mut segments = vec![gauss_kronrod(f, a, b)?];
+    // Halvings in a row of the segments at `a` and at `b` that did not reduce the error.
+    let mut stagnant = [0; 2];
+
+    loop {
+        let value: f64 = segments.iter().map(|segment| segment.value).sum();
+        let error: f64 = segments.iter().map(|segment| segment.error).sum();
+        if error <= TOLERANCE.max(TOLERANCE * value.abs()) {
+            return Ok(Estimate { value, error });
+        }
+        if !value.is_finite() || value.abs() > 1e100 {
+            return Err(Error::Divergent {
+                estimate: value,
+                error,
+            });
+        }
+        if segments.len() >= MAX_SUBINTERVALS {
+            return Err(Error::NoConvergence {
+                estimate: value,
+                error,
+            });
+        }
+
+        let worst = segments
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.error.total_cmp(&y.error))
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+        let segment = segments.swap_remove(worst);
+        let middle = 0.5 * (segment.a + segment.b);
+        // Running out of floating-point resolution, or reaching an infinite bound,
+        // means the error is concentrated at a non-integrable singularity.
+        let divergent = Error::Divergent {
+            estimate: value,
+            error,
+        };
+        if middle <= segment.a || middle >= segment.b {
+            return Err(divergent);
+        }
+        for (start, end) in [(segment.a, middle), (middle, segment.b)] {
+            let half = match gauss_kronrod(f, start, end) {
+                Ok(half) => half,
+                Err(Error::NotFinite(x)) if x.is_infinite() => return Err(divergent),
+                Err(error) => return Err(error),
+            };
+            let side = match (start == a, end == b) {
+                (true, _) => Some(0),
+                (_, true) => Some(1),
+                _ => None,
+            };
+            if let Some(side) = side {
+                if half.error < segment.error * (1.0 - 1e-6) {
+                    stagnant[side] = 0;
+                } else {
+                    stagnant[side] += 1;
+                    if stagnant[side] >= STAGNANT_HALVINGS {
+                        return Err(divergent);
+                    }
+                }
+            }
+            segments.push(half);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(input: &str) -> Result<f64, expr::Error> {
+        expr::evaluate(input, &Context::default()).and_then(|value| value.number())
+    }
+
+    fn close(input: &str, expected: f64) {
+        let value = evaluate(input).unwrap();
+        assert!((value - expected).abs() < 1e-8, "{input} = {value}");
+    }
+
+    #[test]
+    fn derivative() {
+        close("diff(x^3, 2)", 12.0);
+        close("d/dx sin(x) at 0", 1.0);
+        close("d/dx (x^2 + 1) at 3", 6.0);
+    }
+
+    #[test]
+    fn integral() {
+        close("integral(x^2, 0, 3)", 9.0);
+        close("∫ x^2 dx from 0 to 3", 9.0);
+        close("∫(x + 1) dx from 0 to 2", 4.0);
+        close(
+            "∫ exp(-x^2) dx from -inf to inf",
+            std::f64::consts::PI.sqrt(),
+        );
+        close("∫ 1/sqrt(x) dx from 0 to 1", 2.0);
+        close("∫ x dx from 1 to 0", -0.5);
+        close("∫ ln(x) dx from 0 to 1", -1.0);
+        close("∫ sin(100x) dx from 0 to 10", (1.0 - 1000f64.cos()) / 100.0);
+    }
+
+    #[test]
+    fn divergence() {
+        for input in [
+            "integral(1/x, 0, 1)",
+            "∫ 1/x^2 dx from 0 to 1",
+            "∫ 1/x dx from 1 to inf",
+        ] {
+            assert!(
+                matches!(
+                    evaluate(input),
+                    Err(expr::Error::Calculus(Error::Divergent { .. }))
+                ),
+                "{input}: {:?}",
+                evaluate(input)
+            );
+        }
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(
+            evaluate("d/dx x^2"),
+            Err(expr::Error::ExpectedKeyword("at"))
+        );
+        assert_eq!(
+            evaluate("∫ x dx to 1"),
+            Err(expr::Error::ExpectedKeyword("from"))
+        );
+    }
+}
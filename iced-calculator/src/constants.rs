@@ -0,0 +1,204 @@
+use std::f64::consts::{E, PI, SQRT_2};
+
+/// A named constant, usable in expressions by any of its names.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constant {
+    /// The first name is the one the picker inserts.
+    pub names: &'static [&'static str],
+    /// How the constant is written in math, with `_` before a subscript.
+    pub symbol: &'static str,
+    pub description: &'static str,
+    pub value: f64,
+    /// SI unit as an expression, empty for pure numbers.
+    pub unit: &'static str,
+}
+
+const fn constant(
+    names: &'static [&'static str],
+    symbol: &'static str,
+    description: &'static str,
+    value: f64,
+    unit: &'static str,
+) -> Constant {
+    Constant {
+        names,
+        symbol,
+        description,
+        value,
+        unit,
+    }
+}
+
+/// Mathematical constants, then physical ones with their CODATA 2018 values.
+///
+/// Names that would shadow a unit, such as `h` for hours, are spelled out instead.
+pub const CONSTANTS: [Constant; 25] = [
+    constant(&["π", "pi"], "π", "pi", PI, ""),
+    constant(&["e"], "e", "Euler's number", E, ""),
+    constant(
+        &["φ", "phi"],
+        "φ",
+        "golden ratio",
+        1.618_033_988_749_895,
+        "",
+    ),
+    constant(
+        &["γ", "gamma"],
+        "γ",
+        "Euler–Mascheroni constant",
+        0.577_215_664_901_532_9,
+        "",
+    ),
+    constant(&["sqrt2"], "√2", "Pythagoras' constant", SQRT_2, ""),
+    constant(
+        &["c"],
+        "c",
+        "speed of light in vacuum",
+        299_792_458.0,
+        "m/s",
+    ),
+    constant(&["planck"], "h", "Planck constant", 6.626_070_15e-34, "J s"),
+    constant(
+        &["ħ", "hbar"],
+        "ħ",
+        "reduced Planck constant",
+        1.054_571_817e-34,
+        "J s",
+    ),
+    constant(
+        &["G"],
+        "G",
+        "Newtonian constant of gravitation",
+        6.674_30e-11,
+        "m^3/(kg s^2)",
+    ),
+    constant(
+        &["g_n"],
+        "g_n",
+        "standard acceleration of gravity",
+        9.806_65,
+        "m/s^2",
+    ),
+    constant(&["q_e"], "e", "elementary charge", 1.602_176_634e-19, "C"),
+    constant(&["k_B"], "k_B", "Boltzmann constant", 1.380_649e-23, "J/K"),
+    constant(
+        &["N_A"],
+        "N_A",
+        "Avogadro constant",
+        6.022_140_76e23,
+        "1/mol",
+    ),
+    constant(
+        &["R"],
+        "R",
+        "molar gas constant",
+        8.314_462_618,
+        "J/(mol K)",
+    ),
+    constant(&["F"], "F", "Faraday constant", 96_485.332_12, "C/mol"),
+    constant(
+        &["σ", "sigma"],
+        "σ",
+        "Stefan–Boltzmann constant",
+        5.670_374_419e-8,
+        "W/(m^2 K^4)",
+    ),
+    constant(
+        &["ε_0", "epsilon_0"],
+        "ε_0",
+        "vacuum electric permittivity",
+        8.854_187_812_8e-12,
+        "C/(V m)",
+    ),
+    constant(
+        &["μ_0", "mu_0"],
+        "μ_0",
+        "vacuum magnetic permeability",
+        1.256_637_062_12e-6,
+        "N/A^2",
+    ),
+    constant(
+        &["α", "alpha"],
+        "α",
+        "fine-structure constant",
+        7.297_352_569_3e-3,
+        "",
+    ),
+    constant(&["m_e"], "m_e", "electron mass", 9.109_383_701_5e-31, "kg"),
+    constant(&["m_p"], "m_p", "proton mass", 1.672_621_923_69e-27, "kg"),
+    constant(&["m_n"], "m_n", "neutron mass", 1.674_927_498_04e-27, "kg"),
+    constant(
+        &["m_u"],
+        "m_u",
+        "atomic mass constant",
+        1.660_539_066_60e-27,
+        "kg",
+    ),
+    constant(&["a_0"], "a_0", "Bohr radius", 5.291_772_109_03e-11, "m"),
+    constant(
+        &["R_inf"],
+        "R_∞",
+        "Rydberg constant",
+        10_973_731.568_160,
+        "1/m",
+    ),
+];
+
+pub fn lookup(name: &str) -> Option<&'static Constant> {
+    CONSTANTS
+        .iter()
+        .find(|constant| constant.names.contains(&name))
+}
+
+/// Constants whose names, symbol or description contain `query`, ignoring case.
+pub fn search(query: &str) -> impl Iterator<Item = &'static Constant> {
+    let query = query.trim().to_lowercase();
+    CONSTANTS.iter().filter(move |constant| {
+        constant
+            .names
+            .iter()
+            .chain([&constant.symbol, &constant.description])
+            .any(|text| text.to_lowercase().contains(&query))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{evaluate, Context, Value};
+
+    fn value(input: &str) -> f64 {
+        match evaluate(input, &Context::default()).unwrap() {
+            Value::Number(n) => n,
+            Value::Quantity(quantity) => quantity.value,
+            other => panic!("{input} gave {other}"),
+        }
+    }
+
+    #[test]
+    fn lookup_by_any_name() {
+        assert_eq!(lookup("pi").map(|constant| constant.value), Some(PI));
+        assert_eq!(lookup("ħ"), lookup("hbar"));
+        assert_eq!(lookup("speed of light"), None);
+        assert!(search("BOLTZ").any(|constant| constant.symbol == "k_B"));
+    }
+
+    #[test]
+    fn names_that_clash() {
+        // `c` alone is the speed of light; as a prefix it is still centi.
+        assert_eq!(value("c"), 299_792_458.0);
+        assert_eq!(value("3 cm"), 0.03);
+        // `h` stays the hour, so Planck's constant is spelled out.
+        assert_eq!(lookup("h"), None);
+        assert_eq!(value("h"), 3600.0);
+        assert_eq!(value("2 h"), 7200.0);
+        assert_eq!(value("planck"), 6.626_070_15e-34);
+        // G, R and F name no unit, so they are the constants even after a number.
+        assert_eq!(value("G"), 6.674_30e-11);
+        assert_eq!(value("2 R"), 2.0 * 8.314_462_618);
+        assert_eq!(value("F"), 96_485.332_12);
+        // `φ` is the golden ratio, and Euler's totient when called.
+        assert_eq!(value("φ"), 1.618_033_988_749_895);
+        assert_eq!(value("φ(36)"), 12.0);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::locale::Locale;
+use crate::units::{Dimension, Unit};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Age in days after which rates are reported as stale, unless configured otherwise.
+pub const DEFAULT_MAX_AGE_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Io(String),
+    Json(String),
+    Csv { line: usize, message: String },
+    InvalidDate(String),
+    MissingDate,
+    InvalidCode(String),
+    InvalidRate { code: String, rate: String },
+    MissingBase,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(message) => write!(f, "{message}"),
+            Error::Json(message) => write!(f, "invalid rate table: {message}"),
+            Error::Csv { line, message } => write!(f, "line {line}: {message}"),
+            Error::InvalidDate(date) => write!(f, "'{date}' is not a YYYY-MM-DD date"),
+            Error::MissingDate => write!(f, "the rate table has no date"),
+            Error::InvalidCode(code) => write!(f, "'{code}' is not a three-letter currency code"),
+            Error::InvalidRate { code, rate } => {
+                write!(f, "rate '{rate}' for {code} is not a positive number")
+            }
+            Error::MissingBase => write!(f, "the rate table has no base currency"),
+        }
+    }
+}
+
+/// Exchange rates as units of each currency per one unit of `base`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub base: String,
+    pub date: NaiveDate,
+    pub rates: BTreeMap<String, f64>,
+}
+
+impl Table {
+    /// Reads a `.csv` table, or JSON for any other extension.
+    pub fn load(path: &Path) -> Result<Table, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| Error::Io(format!("cannot read {}: {error}", path.display())))?;
+        let table = if has_csv_extension(path) {
+            Table::from_csv(&contents)?
+        } else {
+            serde_json::from_str(&contents).map_err(|error| Error::Json(error.to_string()))?
+        };
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let contents = if has_csv_extension(path) {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(self).map_err(|error| Error::Json(error.to_string()))?
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|error| {
+                Error::Io(format!("cannot create {}: {error}", parent.display()))
+            })?;
+        }
+        std::fs::write(path, contents)
+            .map_err(|error| Error::Io(format!("cannot write {}: {error}", path.display())))
+    }
+
+    /// Parses `base,EUR`, `date,2026-10-01` and `USD,1.08` lines; `#` starts a comment.
+    fn from_csv(contents: &str) -> Result<Table, Error> {
+        let mut base = None;
+        let mut date = None;
+        let mut rates = BTreeMap::new();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let csv_error = |message: String| Error::Csv {
+                line: index + 1,
+                message,
+            };
+            let Some((key, value)) = line.split_once(',') else {
+                return Err(csv_error(format!("expected 'code,rate', got '{line}'")));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key.to_ascii_lowercase().as_str() {
+                "base" => base = Some(value.to_ascii_uppercase()),
+                "date" => date = Some(parse_date(value)?),
+                "currency" | "code" => {}
+                _ => {
+                    let rate = value.parse().map_err(|_| {
+                        csv_error(format!("rate '{value}' for {key} is not a number"))
+                    })?;
+                    rates.insert(key.to_ascii_uppercase(), rate);
+                }
+            }
+        }
+
+        Ok(Table {
+            base: base.ok_or(Error::MissingBase)?,
+            date: date.ok_or(Error::MissingDate)?,
+            rates,
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = format!(
+            "base,{}\ndate,{}\n",
+            self.base,
+            self.date.format(DATE_FORMAT)
+        );
+        for (code, rate) in &self.rates {
+            csv.push_str(&format!("{code},{rate}\n"));
+        }
+        csv
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        for (code, rate) in self.rates.iter().chain([(&self.base, &1.0)]) {
+            if !is_code(code) {
+                return Err(Error::InvalidCode(code.clone()));
+            }
+            if !(rate.is_finite() && *rate > 0.0) {
+                return Err(Error::InvalidRate {
+                    code: code.clone(),
+                    rate: rate.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The currency as a unit whose factor is its value in the base currency.
+    pub fn unit(&self, code: &str) -> Option<Unit> {
+        let rate = if code == self.base {
+            1.0
+        } else {
+            *self.rates.get(code)?
+        };
+        Some(Unit {
+            name: code.to_string(),
+            factor: 1.0 / rate,
+            offset: 0.0,
+            dimension: Dimension::MONEY,
+        })
+    }
+
+    /// Describes the rate date, warning when it is older than `max_age_days`.
+    pub fn status(&self, today: NaiveDate, max_age_days: i64) -> String {
+        let age = (today - self.date).num_days();
+        let date = self.date.format(DATE_FORMAT);
+        if age > max_age_days {
+            format!("⚠ rates from {date} are {age} days old (limit {max_age_days})")
+        } else {
+            format!("rates from {date}")
+        }
+    }
+}
+
+fn has_csv_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+}
+
+fn is_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+        .map_err(|_| Error::InvalidDate(date.to_string()))
+}
+
+/// Where the rate table is kept between sessions.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("iced-calculator").join("rates.json"))
+}
+
+/// Text being edited in the rate table editor.
+#[derive(Debug, Clone, Default)]
+pub struct Editor {
+    pub base: String,
+    pub date: String,
+    pub rows: Vec<(String, String)>,
+}
+
+impl Editor {
+    /// The table's rates written for `locale`.
+    pub fn new(table: &Table, locale: Locale) -> Self {
+        Self {
+            base: table.base.clone(),
+            date: table.date.format(DATE_FORMAT).to_string(),
+            rows: table
+                .rates
+                .iter()
+                .map(|(code, rate)| (code.clone(), locale.localize(&rate.to_string())))
+                .collect(),
+        }
+    }
+
+    /// Reads the rows back, with rates written for `locale`.
+    pub fn table(&self, locale: Locale) -> Result<Table, Error> {
+        let mut rates = BTreeMap::new();
+        for (code, rate) in &self.rows {
+            let code = code.trim().to_ascii_uppercase();
+            if code.is_empty() {
+                continue;
+            }
+            let value = locale
+                .delocalize(rate.trim())
+                .ok()
+                .and_then(|rate| rate.parse().ok())
+                .ok_or_else(|| Error::InvalidRate {
+                    code: code.clone(),
+                    rate: rate.clone(),
+                })?;
+            rates.insert(code, value);
+        }
+        let base = self.base.trim().to_ascii_uppercase();
+        if base.is_empty() {
+            return Err(Error::MissingBase);
+        }
+        if self.date.trim().is_empty() {
+            return Err(Error::MissingDate);
+        }
+        let table = Table {
+            base,
+            date: parse_date(&self.date)?,
+            rates,
+        };
+        table.validate()?;
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn csv() {
+        let table = Table::from_csv(
+            "# rates from the central bank\n\
+             currency,rate\n\
+             base,eur\n\
+             date,2026-10-01\n\
+             \n\
+             usd, 1.08  # US dollar\n\
+             GBP,0.86\n",
+        )
+        .unwrap();
+        assert_eq!(table.base, "EUR");
+        assert_eq!(table.date, date("2026-10-01"));
+        assert_eq!(table.rates.len(), 2);
+        assert_eq!(table.rates["USD"], 1.08);
+        assert_eq!(Table::from_csv(&table.to_csv()), Ok(table));
+
+        assert_eq!(
+            Table::from_csv("base,EUR\nUSD,1.08"),
+            Err(Error::MissingDate)
+        );
+        assert_eq!(
+            Table::from_csv("date,2026-10-01\nUSD,1.08"),
+            Err(Error::MissingBase)
+        );
+        assert!(matches!(
+            Table::from_csv("base,EUR\ndate,2026-10-01\nUSD 1.08"),
+            Err(Error::Csv { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn validation() {
+        let table = |code: &str, rate: f64| Table {
+            base: "EUR".into(),
+            date: date("2026-10-01"),
+            rates: BTreeMap::from([(code.to_string(), rate)]),
+        };
+        assert_eq!(table("USD", 1.08).validate(), Ok(()));
+        assert_eq!(
+            table("usd", 1.08).validate(),
+            Err(Error::InvalidCode("usd".into()))
+        );
+        assert_eq!(
+            table("USD", 0.0).validate(),
+            Err(Error::InvalidRate {
+                code: "USD".into(),
+                rate: "0".into()
+            })
+        );
+    }
+
+    #[test]
+    fn status() {
+        let table = Table {
+            base: "EUR".into(),
+            date: date("2026-10-01"),
+            rates: BTreeMap::new(),
+        };
+        assert_eq!(table.status(date("2026-10-08"), 7), "rates from 2026-10-01");
+        assert_eq!(
+            table.status(date("2026-10-09"), 7),
+            "⚠ rates from 2026-10-01 are 8 days old (limit 7)"
+        );
+    }
+
+    #[test]
+    fn editor_round_trip() {
+        let table = Table {
+            base: "EUR".into(),
+            date: date("2026-10-01"),
+            rates: BTreeMap::from([("USD".into(), 1.08), ("JPY".into(), 161.5)]),
+        };
+        let mut editor = Editor::new(&table, Locale::German);
+        assert_eq!(editor.rows[1], ("USD".into(), "1,08".into()));
+        assert_eq!(editor.table(Locale::German), Ok(table.clone()));
+        // The rates are written for the locale they were shown in.
+        assert!(matches!(
+            editor.table(Locale::English),
+            Err(Error::InvalidRate { .. })
+        ));
+        editor.date.clear();
+        assert_eq!(editor.table(Locale::German), Err(Error::MissingDate));
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use std::fmt;
+
+const MINUTE: u64 = 60_000;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const DAYS_PER_WEEK: u64 = 7;
+/// From this many days on, whole-day durations also show weeks.
+const WEEKS_FROM: u64 = 14;
+
+/// Suffixes of a compact duration such as `3h 20m`, in milliseconds, longest first.
+const SUFFIXES: [(char, u64); 4] = [('d', DAY), ('h', HOUR), ('m', MINUTE), ('s', 1_000)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    OutOfRange,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::OutOfRange => write!(f, "date out of range"),
+        }
+    }
+}
+
+/// A date or duration written directly in an expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Literal {
+    Date(NaiveDateTime),
+    Duration(Duration),
+}
+
+/// A span of time, shown as days, weeks or hh:mm:ss.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Duration {
+    pub seconds: f64,
+}
+
+impl Duration {
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self { seconds }
+    }
+
+    /// The time from `start` to `end`, negative if `end` comes first.
+    pub fn between(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        Self::from_seconds((end - start).num_milliseconds() as f64 / 1_000.0)
+    }
+
+    fn milliseconds(self) -> i64 {
+        (self.seconds * 1_000.0).round() as i64
+    }
+
+    /// Writes the duration the way it is typed, as in `3h 20m`.
+    pub fn compact(self) -> String {
+        let mut rest = self.milliseconds().unsigned_abs();
+        let mut parts = Vec::new();
+        for (suffix, length) in SUFFIXES {
+            let count = if suffix == 's' {
+                rest as f64 / 1_000.0
+            } else {
+                (rest / length) as f64
+            };
+            if count != 0.0 {
+                parts.push(format!("{count}{suffix}"));
+                rest %= length;
+            }
+        }
+        let sign = if self.seconds < 0.0 { "-" } else { "" };
+        if parts.is_empty() {
+            "0s".to_string()
+        } else {
+            format!("{sign}{}", parts.join(" "))
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let milliseconds = self.milliseconds();
+        if milliseconds < 0 {
+            write!(f, "-")?;
+        }
+        // Unsigned, since a saturated i64::MIN has no positive counterpart.
+        let milliseconds = milliseconds.unsigned_abs();
+        let days = milliseconds / DAY;
+        let rest = milliseconds % DAY;
+        let plural = |count: u64, unit: &str| match count {
+            1 => format!("1 {unit}"),
+            n => format!("{n} {unit}s"),
+        };
+
+        if rest == 0 && days > 0 {
+            write!(f, "{}", plural(days, "day"))?;
+            if days >= WEEKS_FROM {
+                write!(f, " ({}", plural(days / DAYS_PER_WEEK, "week"))?;
+                if !days.is_multiple_of(DAYS_PER_WEEK) {
+                    write!(f, " {}", plural(days % DAYS_PER_WEEK, "day"))?;
+                }
+                write!(f, ")")?;
+            }
+            return Ok(());
+        }
+        if days > 0 {
+            write!(f, "{} ", plural(days, "day"))?;
+        }
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            rest / HOUR,
+            rest % HOUR / MINUTE,
+            rest % MINUTE / 1_000
+        )?;
+        match rest % 1_000 {
+            0 => Ok(()),
+            fraction => write!(f, ".{fraction:03}"),
+        }
+    }
+}
+
+/// The current local date and time.
+pub fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
+
+/// The start of the current local day.
+pub fn today() -> NaiveDateTime {
+    Local::now().date_naive().and_time(NaiveTime::MIN)
+}
+
+/// Moves `date` by `duration`, which may be negative.
+pub fn shift(date: NaiveDateTime, duration: Duration) -> Result<NaiveDateTime, Error> {
+    TimeDelta::try_milliseconds(duration.milliseconds())
+        .and_then(|delta| date.checked_add_signed(delta))
+        .ok_or(Error::OutOfRange)
+}
+
+/// Writes a date as it is typed, leaving out a midnight time.
+pub fn format_literal(date: NaiveDateTime) -> String {
+    if date.time() == NaiveTime::MIN {
+        date.format("%Y-%m-%d").to_string()
+    } else {
+        date.format("%Y-%m-%dT%H:%M:%S").to_string()
+    }
+}
+
+/// Writes a date as a result, with its weekday.
+pub fn format(date: NaiveDateTime) -> String {
+    if date.time() == NaiveTime::MIN {
+        date.format("%Y-%m-%d (%A)").to_string()
+    } else {
+        date.format("%Y-%m-%d %H:%M:%S (%A)").to_string()
+    }
+}
+
+/// Reads a date such as `2026-12-25` or `2026-12-25 14:30`, or a duration of two or
+/// more parts such as `3h 20m`, from the start of `chars`.
+///
+/// Returns the literal and how many characters it takes. Text shaped like a date that
+/// is not a real one, such as `2000-13-01`, is left to be read as a subtraction. In a
+/// duration `m` means minutes; a single `20m` is still 20 metres.
+pub fn literal(chars: &[char]) -> Option<(Literal, usize)> {
+    if let Some((date, length)) = date(chars) {
+        return Some((Literal::Date(date), length));
+    }
+    duration(chars).map(|(duration, length)| (Literal::Duration(duration), length))
+}
+
+/// The number written with exactly `count` digits at `start`.
+fn digits(chars: &[char], start: usize, count: usize) -> Option<u32> {
+    let digits = chars.get(start..start + count)?;
+    if !digits.iter().all(char::is_ascii_digit) {
+        return None;
+    }
+    digits.iter().collect::<String>().parse().ok()
+}
+
+/// Year, month and day of anything shaped like `yyyy-mm-dd`.
+fn date_parts(chars: &[char]) -> Option<(u32, u32, u32)> {
+    let year = digits(chars, 0, 4)?;
+    if chars.get(4) != Some(&'-') || chars.get(7) != Some(&'-') {
+        return None;
+    }
+    let month = digits(chars, 5, 2)?;
+    let day = digits(chars, 8, 2)?;
+    if chars.get(10).is_some_and(char::is_ascii_digit) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+fn date(chars: &[char]) -> Option<(NaiveDateTime, usize)> {
+    let (year, month, day) = date_parts(chars)?;
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    let time = match chars.get(10) {
+        Some('T' | ' ') => time(&chars[11..]),
+        _ => None,
+    };
+    Some(match time {
+        Some((time, length)) => (date.and_time(time), 11 + length),
+        None => (date.and_time(NaiveTime::MIN), 10),
+    })
+}
+
+/// Reads `14:30` or `14:30:15`.
+fn time(chars: &[char]) -> Option<(NaiveTime, usize)> {
+    let hour = digits(chars, 0, 2)?;
+    if chars.get(2) != Some(&':') {
+        return None;
+    }
+    let minute = digits(chars, 3, 2)?;
+    let (second, length) = match chars.get(5) {
+        Some(':') => (digits(chars, 6, 2)?, 8),
+        _ => (0, 5),
+    };
+    if chars.get(length).is_some_and(char::is_ascii_digit) {
+        return None;
+    }
+    Some((NaiveTime::from_hms_opt(hour, minute, second)?, length))
+}
+
+/// Reads parts like `3h`, `20m`, each with a shorter unit than the one before.
+fn duration(chars: &[char]) -> Option<(Duration, usize)> {
+    let mut seconds = 0.0;
+    let mut parts = 0;
+    let mut previous = None;
+    let mut end = 0;
+    let mut i = 0;
+    loop {
+        let start = i;
+        while chars
+            .get(i)
+            .is_some_and(|&c| c.is_ascii_digit() || c == '.')
+        {
+            i += 1;
+        }
+        let Some(unit) = chars
+            .get(i)
+            .and_then(|&c| SUFFIXES.iter().position(|&(suffix, _)| suffix == c))
+        else {
+            break;
+        };
+        let whole_word = !chars
+            .get(i + 1)
+            .is_some_and(|&c| c.is_alphanumeric() || c == '_');
+        if i == start || !whole_word || previous.is_some_and(|previous| unit <= previous) {
+            break;
+        }
+        let amount: f64 = chars[start..i].iter().collect::<String>().parse().ok()?;
+        seconds += amount * SUFFIXES[unit].1 as f64 / 1_000.0;
+        parts += 1;
+        previous = Some(unit);
+        i += 1;
+        end = i;
+        while chars.get(i) == Some(&' ') {
+            i += 1;
+        }
+    }
+    (parts >= 2).then_some((Duration::from_seconds(seconds), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{evaluate, Context};
+
+    fn text(input: &str) -> String {
+        evaluate(input, &Context::default()).unwrap().to_string()
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(text("2026-12-25 + 3d 12h"), "2026-12-28 12:00:00 (Monday)");
+        assert_eq!(text("2026-03-01 - 1d 0h"), "2026-02-28 (Saturday)");
+        assert_eq!(text("2026-12-25 - 2026-10-18"), "68 days (9 weeks 5 days)");
+        assert_eq!(text("2026-10-18 14:30 - 2026-10-18"), "14:30:00");
+        assert_eq!(text("3h 20m * 3"), "10:00:00");
+    }
+
+    #[test]
+    fn literals() {
+        let literal = |text: &str| literal(&text.chars().collect::<Vec<_>>());
+        assert_eq!(
+            literal("1h 30m + 1"),
+            Some((Literal::Duration(Duration::from_seconds(5_400.0)), 6))
+        );
+        // A duration needs two parts, so `20m` stays 20 metres.
+        assert_eq!(literal("20m"), None);
+        // Parts must get shorter, so `20m 3h` is not one duration.
+        assert_eq!(literal("20m 3h"), None);
+        // Only real dates are dates; anything else is a subtraction.
+        assert_eq!(literal("2000-13-01"), None);
+        assert_eq!(text("2000-13-01"), "1986");
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Duration::from_seconds(90.5).to_string(), "00:01:30.500");
+        assert_eq!(Duration::from_seconds(-86_400.0).to_string(), "-1 day");
+        assert_eq!(Duration::from_seconds(5_400.0).compact(), "1h 30m");
+        assert_eq!(
+            Duration::from_seconds(-f64::MAX).compact().chars().next(),
+            Some('-')
+        );
+        // Saturates at i64::MIN milliseconds, which has no positive counterpart.
+        assert!(Duration::from_seconds(-1e300)
+            .to_string()
+            .starts_with("-106751991167 days"));
+    }
+}
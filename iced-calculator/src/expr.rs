@@ -0,0 +1,1373 @@
+use crate::calculus;
+use crate::constants::{self, Constant};
+use crate::currency;
+use crate::dates::{self, Duration};
+use crate::finance;
+use crate::locale;
+use crate::matrix::{self, Matrix};
+use crate::number_theory::{self, Factorization};
+use crate::uncertainty::{self, Propagation, Uncertain};
+use crate::units::{self, Quantity, Unit};
+use crate::{AngleMode, LogFunction, Operator, TrigFunction};
+use chrono::NaiveDateTime;
+use rand::Rng;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A parsed arithmetic expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// An integer literal past 2^53, kept exactly for the number theory functions.
+    Integer(u64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Factorial(Box<Expr>),
+    Binary(Operator, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    /// `value to unit`
+    Convert(Box<Expr>, Box<Expr>),
+    /// `value ± uncertainty`
+    Uncertain(Box<Expr>, Box<Expr>),
+    Date(NaiveDateTime),
+    Duration(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    UnexpectedChar(char),
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArgumentCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    Domain(String),
+    ExpectedKeyword(&'static str),
+    ExpectedNumber(&'static str),
+    ExpectedMatrix(&'static str),
+    Matrix(matrix::Error),
+    Unit(units::Error),
+    Calculus(calculus::Error),
+    Finance(finance::Error),
+    Uncertainty(uncertainty::Error),
+    Locale(locale::Error),
+    Date(dates::Error),
+}
+
+impl From<matrix::Error> for Error {
+    fn from(error: matrix::Error) -> Self {
+        Error::Matrix(error)
+    }
+}
+
+impl From<units::Error> for Error {
+    fn from(error: units::Error) -> Self {
+        Error::Unit(error)
+    }
+}
+
+impl From<calculus::Error> for Error {
+    fn from(error: calculus::Error) -> Self {
+        Error::Calculus(error)
+    }
+}
+
+impl From<finance::Error> for Error {
+    fn from(error: finance::Error) -> Self {
+        Error::Finance(error)
+    }
+}
+
+impl From<uncertainty::Error> for Error {
+    fn from(error: uncertainty::Error) -> Self {
+        Error::Uncertainty(error)
+    }
+}
+
+impl From<dates::Error> for Error {
+    fn from(error: dates::Error) -> Self {
+        Error::Date(error)
+    }
+}
+
+impl From<locale::Error> for Error {
+    fn from(error: locale::Error) -> Self {
+        Error::Locale(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedChar(c) => write!(f, "unexpected character '{c}'"),
+            Error::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            Error::UnexpectedToken(token) => write!(f, "unexpected '{token}'"),
+            Error::UnknownVariable(name) => write!(f, "unknown variable '{name}'"),
+            Error::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            Error::WrongArgumentCount {
+                name,
+                expected,
+                found,
+            } => write!(f, "{name} takes {expected} argument(s), got {found}"),
+            Error::Domain(message) => write!(f, "{message}"),
+            Error::ExpectedKeyword(keyword) => write!(f, "expected '{keyword}'"),
+            Error::ExpectedNumber(found) => write!(f, "expected a plain number, got {found}"),
+            Error::ExpectedMatrix(found) => write!(f, "expected a matrix, got {found}"),
+            Error::Matrix(error) => write!(f, "{error}"),
+            Error::Unit(error) => write!(f, "{error}"),
+            Error::Calculus(error) => write!(f, "{error}"),
+            Error::Finance(error) => write!(f, "{error}"),
+            Error::Uncertainty(error) => write!(f, "{error}"),
+            Error::Locale(error) => write!(f, "{error}"),
+            Error::Date(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Matrix(Matrix),
+    Quantity(Quantity),
+    Uncertain(Uncertain),
+    Factors(Factorization),
+    Date(NaiveDateTime),
+    Duration(Duration),
+}
+
+impl Value {
+    pub fn number(&self) -> Result<f64, Error> {
+        match self {
+            Value::Number(n) => Ok(*n),
+            Value::Factors(factors) => Ok(factors.value() as f64),
+            _ => Err(Error::ExpectedNumber(self.kind())),
+        }
+    }
+
+    fn matrix(&self) -> Result<&Matrix, Error> {
+        match self {
+            Value::Matrix(m) => Ok(m),
+            _ => Err(Error::ExpectedMatrix(self.kind())),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Matrix(_) => "a matrix",
+            Value::Quantity(_) => "a quantity with units",
+            Value::Uncertain(_) => "an uncertain value",
+            Value::Factors(_) => "a factorization",
+            Value::Date(_) => "a date",
+            Value::Duration(_) => "a duration",
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<Matrix> for Value {
+    fn from(m: Matrix) -> Self {
+        Value::Matrix(m)
+    }
+}
+
+impl From<Quantity> for Value {
+    /// Units that cancel out, as in `km / m`, leave a plain number.
+    fn from(q: Quantity) -> Self {
+        if q.dimension.is_none() {
+            Value::Number(q.value)
+        } else {
+            Value::Quantity(q)
+        }
+    }
+}
+
+impl From<Uncertain> for Value {
+    fn from(u: Uncertain) -> Self {
+        Value::Uncertain(u)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{n}"),
+            Value::Matrix(m) => write!(f, "{m}"),
+            Value::Quantity(q) => write!(f, "{q}"),
+            Value::Uncertain(u) => write!(f, "{u}"),
+            Value::Factors(factors) => write!(f, "{factors}"),
+            Value::Date(date) => write!(f, "{}", dates::format(*date)),
+            Value::Duration(duration) => write!(f, "{duration}"),
+        }
+    }
+}
+
+/// Variables and settings an expression is evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub angle_mode: AngleMode,
+    pub propagation: Propagation,
+    variables: HashMap<String, Value>,
+    currencies: Option<(currency::Table, String)>,
+    notes: RefCell<Vec<String>>,
+}
+
+impl Context {
+    pub fn new(angle_mode: AngleMode) -> Self {
+        Self {
+            angle_mode,
+            propagation: Propagation::default(),
+            variables: HashMap::new(),
+            currencies: None,
+            notes: RefCell::default(),
+        }
+    }
+
+    /// Makes currency codes usable as units; `status` is noted whenever one is used.
+    pub fn set_currencies(&mut self, table: currency::Table, status: String) {
+        self.currencies = Some((table, status));
+    }
+
+    /// Records extra information about the evaluation, such as an error estimate.
+    pub fn note(&self, note: String) {
+        let mut notes = self.notes.borrow_mut();
+        if !notes.contains(&note) {
+            notes.push(note);
+        }
+    }
+
+    pub fn take_notes(&self) -> Vec<String> {
+        self.notes.take()
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) {
+        self.variables.insert(name.to_string(), value.into());
+    }
+
+    fn unit(&self, name: &str) -> Option<Unit> {
+        Unit::lookup(name).or_else(|| {
+            let (table, status) = self.currencies.as_ref()?;
+            let unit = table.unit(name)?;
+            self.note(status.clone());
+            Some(unit)
+        })
+    }
+
+    fn is_variable(&self, name: &str) -> bool {
+        self.variables.contains_key(name)
+    }
+
+    fn get(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.variables.get(name) {
+            return Some(value.clone());
+        }
+        if let Some(constant) = constants::lookup(name) {
+            return constant_value(constant);
+        }
+        match name {
+            "inf" => Some(Value::Number(f64::INFINITY)),
+            "now" => Some(Value::Date(dates::now())),
+            "today" => Some(Value::Date(dates::today())),
+            _ => self
+                .unit(name)
+                .map(|unit| Value::Quantity(Quantity::new(1.0, unit))),
+        }
+    }
+}
+
+/// A constant as a number, or as a quantity when it has a unit.
+fn constant_value(constant: &Constant) -> Option<Value> {
+    if constant.unit.is_empty() {
+        return Some(Value::Number(constant.value));
+    }
+    match evaluate(constant.unit, &Context::default()).ok()? {
+        Value::Quantity(unit) => Some(Value::Quantity(unit.scale(constant.value))),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Integer(u64),
+    Date(NaiveDateTime),
+    Duration(Duration),
+    Ident(String),
+    Operator(Operator),
+    PlusMinus,
+    Bang,
+    Comma,
+    LeftParen,
+    RightParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{n}"),
+            Token::Integer(n) => write!(f, "{n}"),
+            Token::Date(date) => write!(f, "{}", dates::format_literal(*date)),
+            Token::Duration(duration) => write!(f, "{}", duration.compact()),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Operator(op) => write!(f, "{}", op.symbol()),
+            Token::PlusMinus => write!(f, "±"),
+            Token::Bang => write!(f, "!"),
+            Token::Comma => write!(f, ","),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        // Dates and durations such as `2026-12-25` and `3h 20m` read as one token.
+        let literal = if c.is_ascii_digit() {
+            dates::literal(&chars[i..])
+        } else {
+            None
+        };
+        if let Some((literal, length)) = literal {
+            tokens.push(match literal {
+                dates::Literal::Date(date) => Token::Date(date),
+                dates::Literal::Duration(duration) => Token::Duration(duration),
+            });
+            i += length;
+            continue;
+        }
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Only treat `e` as an exponent when digits follow, so `2e` is still 2·e.
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let literal: String = chars[start..i].iter().collect();
+                match literal.parse::<u64>() {
+                    Ok(n) if n > MAX_EXACT_INTEGER as u64 => tokens.push(Token::Integer(n)),
+                    _ => {
+                        let value = literal
+                            .parse::<f64>()
+                            .map_err(|_| Error::UnexpectedToken(literal))?;
+                        tokens.push(Token::Number(value));
+                    }
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '°' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => {
+                tokens.push(match c {
+                    '+' => Token::Operator(Operator::Add),
+                    '-' | '−' => Token::Operator(Operator::Subtract),
+                    '*' | '×' | '·' => Token::Operator(Operator::Multiply),
+                    '/' | '÷' => Token::Operator(Operator::Divide),
+                    '^' => Token::Operator(Operator::Exponentiate),
+                    '±' => Token::PlusMinus,
+                    '!' => Token::Bang,
+                    '√' => Token::Ident("sqrt".into()),
+                    '∫' => Token::Ident("integral".into()),
+                    '∞' => Token::Ident("inf".into()),
+                    ',' => Token::Comma,
+                    '(' => Token::LeftParen,
+                    ')' => Token::RightParen,
+                    _ => return Err(Error::UnexpectedChar(c)),
+                });
+                i += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses `input` into an expression tree.
+pub fn parse(input: &str) -> Result<Expr, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    let expr = parser.conversion()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+    }
+}
+
+/// Parses and evaluates `input` in one step.
+pub fn evaluate(input: &str, context: &Context) -> Result<Value, Error> {
+    parse(input)?.eval(context)
+}
+
+const CONVERT_KEYWORD: &str = "to";
+
+// Words of `d/dx f(x) at x0` and `∫ f(x) dx from a to b`.
+const DIFFERENTIAL: &str = "dx";
+const DERIVATIVE_POINT: &str = "at";
+const LOWER_BOUND: &str = "from";
+const UPPER_BOUND: &str = CONVERT_KEYWORD;
+
+/// Words that end an operand rather than multiply it.
+const KEYWORDS: [&str; 4] = [CONVERT_KEYWORD, DIFFERENTIAL, DERIVATIVE_POINT, LOWER_BOUND];
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Error> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+            None => Err(Error::UnexpectedEnd),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &'static str) -> Result<(), Error> {
+        match self.next() {
+            Some(Token::Ident(word)) if word == keyword => Ok(()),
+            _ => Err(Error::ExpectedKeyword(keyword)),
+        }
+    }
+
+    fn is_keyword(&self, offset: usize, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.position + offset), Some(Token::Ident(word)) if word == keyword)
+    }
+
+    /// Whether the `d` just read starts `d/dx`.
+    fn derivative_follows(&self) -> bool {
+        self.peek() == Some(&Token::Operator(Operator::Divide)) && self.is_keyword(1, DIFFERENTIAL)
+    }
+
+    /// Whether the `∫` just read starts `∫ f(x) dx from a to b` rather than the call
+    /// `∫(f, a, b)`; a parenthesized integrand is followed by `dx`.
+    fn integrand_follows(&self) -> bool {
+        if self.peek() != Some(&Token::LeftParen) {
+            return true;
+        }
+        let mut depth = 0;
+        for (offset, token) in self.tokens[self.position..].iter().enumerate() {
+            match token {
+                Token::LeftParen => depth += 1,
+                Token::RightParen if depth == 1 => {
+                    return self.is_keyword(offset + 1, DIFFERENTIAL)
+                }
+                Token::RightParen => depth -= 1,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// `d/dx f(x) at x0`, after the `d`.
+    fn derivative(&mut self) -> Result<Expr, Error> {
+        self.position += 2;
+        let body = self.expression()?;
+        self.keyword(DERIVATIVE_POINT)?;
+        let point = self.expression()?;
+        Ok(Expr::Call("diff".into(), vec![body, point]))
+    }
+
+    /// `∫ f(x) dx from a to b`, after the `∫`.
+    fn integral(&mut self) -> Result<Expr, Error> {
+        let body = self.expression()?;
+        self.keyword(DIFFERENTIAL)?;
+        self.keyword(LOWER_BOUND)?;
+        let lower = self.expression()?;
+        self.keyword(UPPER_BOUND)?;
+        let upper = self.expression()?;
+        Ok(Expr::Call("integral".into(), vec![body, lower, upper]))
+    }
+
+    fn conversion(&mut self) -> Result<Expr, Error> {
+        let value = self.expression()?;
+        match self.peek() {
+            Some(Token::Ident(keyword)) if keyword == CONVERT_KEYWORD => {
+                self.position += 1;
+                Ok(Expr::Convert(Box::new(value), Box::new(self.expression()?)))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Operator(op @ (Operator::Add | Operator::Subtract))) => {
+                    let op = *op;
+                    self.position += 1;
+                    let rhs = self.term()?;
+                    lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+                }
+                Some(Token::PlusMinus) => {
+                    self.position += 1;
+                    let rhs = self.term()?;
+                    lhs = Expr::Uncertain(Box::new(lhs), Box::new(rhs));
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.implicit()?;
+        while let Some(Token::Operator(op @ (Operator::Multiply | Operator::Divide))) = self.peek()
+        {
+            let op = *op;
+            self.position += 1;
+            let rhs = self.implicit()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// Implicit multiplication: `2π`, `3(4 + 1)`, `2 sin(x)`, `5 km`.
+    ///
+    /// It binds tighter than `*` and `/`, so `5 km / 20 min` is `(5 km) / (20 min)`.
+    fn implicit(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Ident(keyword)) if KEYWORDS.contains(&keyword.as_str()) => break,
+                Some(
+                    Token::Number(_)
+                    | Token::Integer(_)
+                    | Token::Date(_)
+                    | Token::Duration(_)
+                    | Token::Ident(_)
+                    | Token::LeftParen,
+                ) => {
+                    let rhs = self.power()?;
+                    lhs = Expr::Binary(Operator::Multiply, Box::new(lhs), Box::new(rhs));
+                }
+                _ => break,
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        match self.peek() {
+            Some(Token::Operator(Operator::Subtract)) => {
+                self.position += 1;
+                Ok(Expr::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator(Operator::Add)) => {
+                self.position += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, Error> {
+        let base = self.postfix()?;
+        if let Some(Token::Operator(Operator::Exponentiate)) = self.peek() {
+            self.position += 1;
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(
+                Operator::Exponentiate,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.primary()?;
+        while let Some(Token::Bang) = self.peek() {
+            self.position += 1;
+            expr = Expr::Factorial(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Integer(n)) => Ok(Expr::Integer(n)),
+            Some(Token::Date(date)) => Ok(Expr::Date(date)),
+            Some(Token::Duration(duration)) => Ok(Expr::Duration(duration)),
+            Some(Token::Ident(name)) if name == "d" && self.derivative_follows() => {
+                self.derivative()
+            }
+            Some(Token::Ident(name)) if name == "integral" && self.integrand_follows() => {
+                self.integral()
+            }
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LeftParen) = self.peek() {
+                    self.position += 1;
+                    let mut args = Vec::new();
+                    if let Some(Token::RightParen) = self.peek() {
+                        self.position += 1;
+                    } else {
+                        loop {
+                            args.push(self.expression()?);
+                            match self.next() {
+                                Some(Token::Comma) => continue,
+                                Some(Token::RightParen) => break,
+                                Some(token) => {
+                                    return Err(Error::UnexpectedToken(token.to_string()))
+                                }
+                                None => return Err(Error::UnexpectedEnd),
+                            }
+                        }
+                    }
+                    Ok(Expr::Call(name, args))
+                } else {
+                    Ok(Expr::Variable(name))
+                }
+            }
+            Some(Token::LeftParen) => {
+                let expr = self.expression()?;
+                self.expect(Token::RightParen)?;
+                Ok(expr)
+            }
+            Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+            None => Err(Error::UnexpectedEnd),
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, context: &Context) -> Result<Value, Error> {
+        match self {
+            Expr::Number(n) => Ok(Value::Number(*n)),
+            Expr::Integer(n) => Ok(Value::Number(*n as f64)),
+            Expr::Date(date) => Ok(Value::Date(*date)),
+            Expr::Duration(duration) => Ok(Value::Duration(*duration)),
+            Expr::Variable(name) => context
+                .get(name)
+                .ok_or_else(|| Error::UnknownVariable(name.clone())),
+            Expr::Negate(expr) => match expr.eval(context)? {
+                Value::Number(n) => Ok(Value::Number(-n)),
+                Value::Matrix(m) => Ok(Value::Matrix(m.scale(-1.0))),
+                Value::Quantity(q) => Ok(Value::Quantity(q.scale(-1.0))),
+                Value::Uncertain(u) => Ok(Value::Uncertain(u.negate())),
+                Value::Factors(factors) => Ok(Value::Number(-(factors.value() as f64))),
+                Value::Duration(duration) => {
+                    Ok(Value::Duration(Duration::from_seconds(-duration.seconds)))
+                }
+                value @ Value::Date(_) => Err(Error::ExpectedNumber(value.kind())),
+            },
+            Expr::Factorial(expr) => Ok(Value::Number(factorial(expr.eval(context)?.number()?)?)),
+            Expr::Binary(Operator::Multiply, lhs, rhs) => {
+                let lhs = lhs.eval(context)?;
+                // `20 °C` is an absolute temperature, not 20 × (1 °C).
+                if let (Value::Number(amount), Some(unit)) = (&lhs, rhs.unit(context)) {
+                    return Ok(Value::Quantity(Quantity::new(*amount, unit)));
+                }
+                binary(Operator::Multiply, lhs, rhs.eval(context)?, context)
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                binary(*op, lhs.eval(context)?, rhs.eval(context)?, context)
+            }
+            Expr::Uncertain(value, uncertainty) => Ok(Value::Uncertain(Uncertain::new(
+                value.eval_number(context)?,
+                uncertainty.eval_number(context)?,
+            ))),
+            Expr::Convert(value, target) => {
+                let unit = match target.unit(context) {
+                    Some(unit) => unit,
+                    None => match target.eval(context)? {
+                        Value::Quantity(q) => Unit {
+                            name: target.to_string(),
+                            factor: q.value,
+                            offset: 0.0,
+                            dimension: q.dimension,
+                        },
+                        _ => return Err(units::Error::UnknownUnit(target.to_string()).into()),
+                    },
+                };
+                let quantity = match value.eval(context)? {
+                    Value::Quantity(q) => q,
+                    Value::Number(n) => Quantity::base(n, units::Dimension::NONE),
+                    Value::Factors(factors) => {
+                        Quantity::base(factors.value() as f64, units::Dimension::NONE)
+                    }
+                    Value::Duration(duration) => {
+                        Quantity::base(duration.seconds, units::Dimension::TIME)
+                    }
+                    value @ (Value::Matrix(_) | Value::Uncertain(_) | Value::Date(_)) => {
+                        return Err(Error::ExpectedNumber(value.kind()))
+                    }
+                };
+                Ok(Value::Quantity(quantity.convert(unit)?))
+            }
+            Expr::Call(name, args) if calculus::is_operator(name) => {
+                Ok(Value::Number(calculus::call(name, args, context)?))
+            }
+            Expr::Call(name, args) => {
+                // Outside these functions a large literal is only as exact as an f64.
+                if let [Expr::Integer(n)] = args.as_slice() {
+                    if NATURAL_FUNCTIONS.contains(&name.as_str()) {
+                        return natural_function(name, *n, context);
+                    }
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(context))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(name, &args, context)
+            }
+        }
+    }
+
+    /// The unit this expression names, unless a variable shadows it.
+    fn unit(&self, context: &Context) -> Option<Unit> {
+        match self {
+            Expr::Variable(name) if !context.is_variable(name) => context.unit(name),
+            _ => None,
+        }
+    }
+
+    /// Evaluates an expression that must produce a plain number.
+    pub fn eval_number(&self, context: &Context) -> Result<f64, Error> {
+        self.eval(context)?.number()
+    }
+}
+
+/// Applies `op` to two values, as the keypad's operator keys do.
+pub fn binary(op: Operator, lhs: Value, rhs: Value, context: &Context) -> Result<Value, Error> {
+    // A factorization is only a way of writing its number.
+    let as_number = |value: Value| match value {
+        Value::Factors(factors) => Value::Number(factors.value() as f64),
+        value => value,
+    };
+    let (lhs, rhs) = (as_number(lhs), as_number(rhs));
+    if [&lhs, &rhs]
+        .iter()
+        .any(|value| matches!(value, Value::Date(_) | Value::Duration(_)))
+    {
+        return temporal(op, lhs, rhs);
+    }
+    let value = match (op, lhs, rhs) {
+        (op, Value::Number(a), Value::Number(b)) => Value::Number(op.apply(a, b)),
+        (op, Value::Uncertain(a), Value::Uncertain(b)) => {
+            a.binary(op, b, context.propagation)?.into()
+        }
+        (op, Value::Uncertain(a), Value::Number(b)) => a
+            .binary(op, Uncertain::exact(b), context.propagation)?
+            .into(),
+        (op, Value::Number(a), Value::Uncertain(b)) => Uncertain::exact(a)
+            .binary(op, b, context.propagation)?
+            .into(),
+        (Operator::Add, Value::Matrix(a), Value::Matrix(b)) => Value::Matrix(a.add(&b)?),
+        (Operator::Subtract, Value::Matrix(a), Value::Matrix(b)) => Value::Matrix(a.subtract(&b)?),
+        (Operator::Multiply, Value::Matrix(a), Value::Matrix(b)) => Value::Matrix(a.multiply(&b)?),
+        (Operator::Multiply, Value::Number(k), Value::Matrix(m))
+        | (Operator::Multiply, Value::Matrix(m), Value::Number(k)) => Value::Matrix(m.scale(k)),
+        (Operator::Divide, Value::Matrix(m), Value::Number(k)) => Value::Matrix(m.scale(1.0 / k)),
+        (Operator::Exponentiate, Value::Matrix(m), Value::Number(k)) => {
+            if k.fract() != 0.0 || k.abs() > i32::MAX as f64 {
+                return Err(Error::Domain(format!(
+                    "matrix power must be an integer, got {k}"
+                )));
+            }
+            Value::Matrix(m.power(k as i32)?)
+        }
+        (Operator::Add, Value::Quantity(a), Value::Quantity(b)) => a.add(&b)?.into(),
+        (Operator::Subtract, Value::Quantity(a), Value::Quantity(b)) => a.subtract(&b)?.into(),
+        (Operator::Multiply, Value::Quantity(a), Value::Quantity(b)) => a.multiply(&b).into(),
+        (Operator::Divide, Value::Quantity(a), Value::Quantity(b)) => a.divide(&b).into(),
+        (Operator::Multiply, Value::Number(k), Value::Quantity(q))
+        | (Operator::Multiply, Value::Quantity(q), Value::Number(k)) => q.scale(k).into(),
+        (Operator::Divide, Value::Quantity(q), Value::Number(k)) => q.scale(1.0 / k).into(),
+        (Operator::Divide, Value::Number(k), Value::Quantity(q)) => {
+            Quantity::base(k, units::Dimension::NONE).divide(&q).into()
+        }
+        (Operator::Exponentiate, Value::Quantity(q), Value::Number(k)) => q.power(k)?.into(),
+        (op @ (Operator::Add | Operator::Subtract), Value::Quantity(q), Value::Number(_))
+        | (op @ (Operator::Add | Operator::Subtract), Value::Number(_), Value::Quantity(q)) => {
+            return Err(units::Error::Incompatible {
+                operation: if op == Operator::Add {
+                    "add"
+                } else {
+                    "subtract"
+                },
+                lhs: q.dimension,
+                rhs: units::Dimension::NONE,
+            }
+            .into())
+        }
+        (op, lhs, rhs) => {
+            return Err(Error::Domain(format!(
+                "'{}' is not defined between {} and {}",
+                op.symbol(),
+                lhs.kind(),
+                rhs.kind()
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Arithmetic on dates and durations, where time quantities such as `90 days` count
+/// as durations.
+fn temporal(op: Operator, lhs: Value, rhs: Value) -> Result<Value, Error> {
+    let duration = |value: &Value| match value {
+        Value::Duration(duration) => Some(*duration),
+        Value::Quantity(q) if q.dimension == units::Dimension::TIME => {
+            Some(Duration::from_seconds(q.value))
+        }
+        _ => None,
+    };
+    let value = match (op, &lhs, &rhs) {
+        (Operator::Subtract, Value::Date(end), Value::Date(start)) => {
+            Some(Value::Duration(Duration::between(*start, *end)))
+        }
+        (Operator::Add, Value::Date(date), span) | (Operator::Add, span, Value::Date(date)) => {
+            match duration(span) {
+                Some(span) => Some(Value::Date(dates::shift(*date, span)?)),
+                None => None,
+            }
+        }
+        (Operator::Subtract, Value::Date(date), span) => match duration(span) {
+            Some(span) => Some(Value::Date(dates::shift(
+                *date,
+                Duration::from_seconds(-span.seconds),
+            )?)),
+            None => None,
+        },
+        (Operator::Multiply, Value::Duration(span), Value::Number(k))
+        | (Operator::Multiply, Value::Number(k), Value::Duration(span)) => {
+            Some(Value::Duration(Duration::from_seconds(span.seconds * k)))
+        }
+        (Operator::Divide, Value::Duration(span), Value::Number(k)) => {
+            Some(Value::Duration(Duration::from_seconds(span.seconds / k)))
+        }
+        (op, lhs, rhs) => match (duration(lhs), duration(rhs)) {
+            (Some(a), Some(b)) => match op {
+                Operator::Add => Some(Value::Duration(Duration::from_seconds(
+                    a.seconds + b.seconds,
+                ))),
+                Operator::Subtract => Some(Value::Duration(Duration::from_seconds(
+                    a.seconds - b.seconds,
+                ))),
+                Operator::Divide => Some(Value::Number(a.seconds / b.seconds)),
+                _ => None,
+            },
+            _ => None,
+        },
+    };
+    value.ok_or_else(|| {
+        Error::Domain(format!(
+            "'{}' is not defined between {} and {}",
+            op.symbol(),
+            lhs.kind(),
+            rhs.kind()
+        ))
+    })
+}
+
+/// Largest `n` whose factorial an `f64` can hold; 171! overflows.
+const MAX_FACTORIAL: f64 = 170.0;
+
+fn factorial(n: f64) -> Result<f64, Error> {
+    // The domain comes first, so that 170.5! is as undefined as 1.5!.
+    if n.is_nan() || n < 0.0 || (n.is_finite() && n.fract() != 0.0) {
+        return Err(Error::Domain(format!("{n}! is not defined")));
+    }
+    if n > MAX_FACTORIAL {
+        return Ok(f64::INFINITY);
+    }
+    Ok((1..=n as u64).map(|k| k as f64).product())
+}
+
+/// Largest integer an `f64` holds exactly.
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+fn integer(name: &str, x: f64) -> Result<f64, Error> {
+    if x.fract() != 0.0 || x.abs() > MAX_EXACT_INTEGER {
+        return Err(Error::Domain(format!("{name} needs integers, got {x}")));
+    }
+    Ok(x)
+}
+
+/// A positive integer argument for the number theory functions.
+fn natural(name: &str, x: f64) -> Result<u64, Error> {
+    let n = integer(name, x)?;
+    if n < 1.0 {
+        return Err(Error::Domain(format!(
+            "{name} needs a positive integer, got {n}"
+        )));
+    }
+    Ok(n as u64)
+}
+
+/// The number theory functions of a single positive integer.
+const NATURAL_FUNCTIONS: [&str; 6] = [
+    "isprime",
+    "factor",
+    "nextprime",
+    "prevprime",
+    "totient",
+    "φ",
+];
+
+fn natural_function(name: &str, n: u64, context: &Context) -> Result<Value, Error> {
+    let value = match name {
+        "isprime" => {
+            let prime = number_theory::is_prime(n);
+            context.note(if prime {
+                format!("{n} is prime")
+            } else {
+                format!("{n} = {}", number_theory::factorize(n))
+            });
+            Value::Number(if prime { 1.0 } else { 0.0 })
+        }
+        "factor" => Value::Factors(number_theory::factorize(n)),
+        "nextprime" => {
+            let prime = number_theory::next_prime(n)
+                .ok_or_else(|| Error::Domain(format!("there is no prime above {n} below 2^64")))?;
+            exact(prime)
+        }
+        "prevprime" => {
+            let prime = number_theory::prev_prime(n)
+                .ok_or_else(|| Error::Domain(format!("there is no prime below {n}")))?;
+            exact(prime)
+        }
+        _ => exact(number_theory::totient(n)),
+    };
+    Ok(value)
+}
+
+/// An integer result; past 2^53 it is kept as its factorization, which shows every digit.
+fn exact(n: u64) -> Value {
+    if n > MAX_EXACT_INTEGER as u64 {
+        Value::Factors(number_theory::factorize(n))
+    } else {
+        Value::Number(n as f64)
+    }
+}
+
+fn gcd(a: f64, b: f64) -> f64 {
+    let (mut a, mut b) = (a.abs() as u64, b.abs() as u64);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as f64
+}
+
+/// `n! / (n − r)!` and, with `choose`, `n! / (r! (n − r)!)`, built up one factor at a
+/// time so that large `n` does not overflow before the result does.
+fn combinatorics(name: &str, n: f64, r: f64, choose: bool) -> Result<f64, Error> {
+    let (n, r) = (integer(name, n)?, integer(name, r)?);
+    if n < 0.0 || r < 0.0 {
+        return Err(Error::Domain(format!("{name}({n}, {r}) is not defined")));
+    }
+    if r > n {
+        return Ok(0.0);
+    }
+    let r = if choose { r.min(n - r) } else { r };
+    let mut result: f64 = 1.0;
+    let mut k = 0.0;
+    while k < r && result.is_finite() {
+        result *= n - k;
+        if choose {
+            result /= k + 1.0;
+        }
+        k += 1.0;
+    }
+    Ok(result.round())
+}
+
+fn arguments<'a, const N: usize>(name: &str, args: &'a [Value]) -> Result<&'a [Value; N], Error> {
+    args.try_into().map_err(|_| Error::WrongArgumentCount {
+        name: name.to_string(),
+        expected: N,
+        found: args.len(),
+    })
+}
+
+fn numbers<const N: usize>(name: &str, args: &[Value]) -> Result<[f64; N], Error> {
+    let args = arguments::<N>(name, args)?;
+    let mut numbers = [0.0; N];
+    for (number, arg) in numbers.iter_mut().zip(args) {
+        *number = arg.number()?;
+    }
+    Ok(numbers)
+}
+
+fn call(name: &str, args: &[Value], context: &Context) -> Result<Value, Error> {
+    let value = match name {
+        "sin" | "cos" | "tan" => {
+            let trig_fn = match name {
+                "sin" => TrigFunction::Sine,
+                "cos" => TrigFunction::Cosine,
+                _ => TrigFunction::Tangent,
+            };
+            match arguments::<1>(name, args)? {
+                [Value::Uncertain(x)] => x
+                    .trig(trig_fn, context.angle_mode, context.propagation)?
+                    .into(),
+                [angle] => Value::Number(trig_fn.apply(angle.number()?, context.angle_mode)),
+            }
+        }
+        "log" | "ln" => {
+            let log_fn = match name {
+                "log" => LogFunction::Log10,
+                _ => LogFunction::Ln,
+            };
+            match arguments::<1>(name, args)? {
+                [Value::Uncertain(x)] => x.log(log_fn, context.propagation)?.into(),
+                [x] => Value::Number(log_fn.apply(x.number()?)),
+            }
+        }
+        "sqrt" | "cbrt" => {
+            let exponent = if name == "sqrt" { 0.5 } else { 1.0 / 3.0 };
+            match arguments::<1>(name, args)? {
+                [Value::Quantity(q)] => q.power(exponent)?.into(),
+                [Value::Uncertain(x)] if name == "sqrt" => x.sqrt(context.propagation)?.into(),
+                [Value::Uncertain(x)] => x.cbrt(context.propagation)?.into(),
+                [x] if name == "sqrt" => Value::Number(x.number()?.sqrt()),
+                [x] => Value::Number(x.number()?.cbrt()),
+            }
+        }
+        "exp" => match arguments::<1>(name, args)? {
+            [Value::Uncertain(x)] => x.exp(context.propagation)?.into(),
+            [x] => Value::Number(x.number()?.exp()),
+        },
+        "root" => {
+            let [x, y] = numbers(name, args)?;
+            Value::Number(x.powf(1.0 / y))
+        }
+        "det" => Value::Number(arguments::<1>(name, args)?[0].matrix()?.determinant()?),
+        "inv" => Value::Matrix(arguments::<1>(name, args)?[0].matrix()?.inverse()?),
+        "transpose" | "trans" => {
+            Value::Matrix(arguments::<1>(name, args)?[0].matrix()?.transpose())
+        }
+        "rank" => Value::Number(arguments::<1>(name, args)?[0].matrix()?.rank() as f64),
+        "solve" => {
+            let [a, b] = arguments(name, args)?;
+            Value::Matrix(a.matrix()?.solve(b.matrix()?)?)
+        }
+        "dot" => {
+            let [u, v] = arguments(name, args)?;
+            Value::Number(u.matrix()?.dot(v.matrix()?)?)
+        }
+        "cross" => {
+            let [u, v] = arguments(name, args)?;
+            Value::Matrix(u.matrix()?.cross(v.matrix()?)?)
+        }
+        "mod" | "div" => {
+            let [a, b] = numbers(name, args)?;
+            if b == 0.0 {
+                return Err(Error::Domain(format!("{name} by zero")));
+            }
+            let quotient = (a / b).floor();
+            Value::Number(if name == "div" {
+                quotient
+            } else {
+                a - b * quotient
+            })
+        }
+        "floor" => Value::Number(numbers::<1>(name, args)?[0].floor()),
+        "ceil" => Value::Number(numbers::<1>(name, args)?[0].ceil()),
+        "abs" => Value::Number(numbers::<1>(name, args)?[0].abs()),
+        "sign" | "sgn" => {
+            let [x] = numbers(name, args)?;
+            Value::Number(if x == 0.0 { 0.0 } else { x.signum() })
+        }
+        "round" => {
+            let (x, places) = match args {
+                [x] => (x.number()?, 0.0),
+                _ => {
+                    let [x, places] = numbers(name, args)?;
+                    (x, integer(name, places)?)
+                }
+            };
+            let scale = 10f64.powf(places.max(f64::MIN_10_EXP as f64));
+            let scaled = x * scale;
+            // Past the last digit a double holds there is nothing left to round.
+            Value::Number(if scaled.is_finite() {
+                scaled.round() / scale
+            } else {
+                x
+            })
+        }
+        "gcd" | "lcm" => {
+            let [a, b] = numbers(name, args)?;
+            let (a, b) = (integer(name, a)?, integer(name, b)?);
+            let divisor = gcd(a, b);
+            Value::Number(match name {
+                "gcd" => divisor,
+                _ if divisor == 0.0 => 0.0,
+                _ => (a / divisor * b).abs(),
+            })
+        }
+        "nCr" | "ncr" | "nPr" | "npr" => {
+            let [n, r] = numbers(name, args)?;
+            Value::Number(combinatorics(name, n, r, name.eq_ignore_ascii_case("ncr"))?)
+        }
+        "rand" => match args {
+            [] => Value::Number(rand::random()),
+            _ => {
+                let [low, high] = numbers(name, args)?;
+                let (low, high) = (integer(name, low)?, integer(name, high)?);
+                if low > high {
+                    return Err(Error::Domain(format!(
+                        "rand({low}, {high}) has an empty range"
+                    )));
+                }
+                Value::Number(rand::thread_rng().gen_range(low as i64..=high as i64) as f64)
+            }
+        },
+        "isprime" | "factor" | "nextprime" | "prevprime" | "totient" | "φ" => {
+            let n = natural(name, numbers::<1>(name, args)?[0])?;
+            natural_function(name, n, context)?
+        }
+        "powmod" => {
+            let [base, exponent, modulus] = numbers(name, args)?;
+            let base = integer(name, base)?;
+            let exponent = integer(name, exponent)?;
+            if exponent < 0.0 {
+                return Err(Error::Domain(format!(
+                    "{name} needs a non-negative exponent"
+                )));
+            }
+            let modulus = natural(name, modulus)?;
+            let base = base.rem_euclid(modulus as f64) as u64;
+            Value::Number(number_theory::pow_mod(base, exponent as u64, modulus) as f64)
+        }
+        "npv" => {
+            let [rate, flows @ ..] = args else {
+                return Err(Error::WrongArgumentCount {
+                    name: name.to_string(),
+                    expected: 2,
+                    found: 0,
+                });
+            };
+            let flows = flows
+                .iter()
+                .map(Value::number)
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::Number(finance::npv(rate.number()?, &flows)?)
+        }
+        "irr" => {
+            let flows = args
+                .iter()
+                .map(Value::number)
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::Number(finance::irr(&flows)?)
+        }
+        _ => return Err(Error::UnknownFunction(name.to_string())),
+    };
+    Ok(value)
+}
+
+impl fmt::Display for Expr {
+    /// Writes the expression back out compactly; used to label units such as `km/h`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter<'_>, expr: &Expr| match expr {
+            Expr::Binary(Operator::Add | Operator::Subtract, ..)
+            | Expr::Convert(..)
+            | Expr::Uncertain(..) => {
+                write!(f, "({expr})")
+            }
+            _ => write!(f, "{expr}"),
+        };
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Integer(n) => write!(f, "{n}"),
+            Expr::Variable(name) => write!(f, "{name}"),
+            Expr::Negate(expr) => {
+                write!(f, "-")?;
+                operand(f, expr)
+            }
+            Expr::Factorial(expr) => {
+                operand(f, expr)?;
+                write!(f, "!")
+            }
+            Expr::Binary(op @ (Operator::Add | Operator::Subtract), lhs, rhs) => {
+                write!(f, "{lhs} {} {rhs}", op.symbol())
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                operand(f, lhs)?;
+                write!(
+                    f,
+                    "{}",
+                    match op {
+                        Operator::Multiply => "·",
+                        Operator::Divide => "/",
+                        _ => "^",
+                    }
+                )?;
+                match **rhs {
+                    Expr::Binary(Operator::Multiply | Operator::Divide, ..) => write!(f, "({rhs})"),
+                    _ => operand(f, rhs),
+                }
+            }
+            Expr::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(Expr::to_string).collect();
+                write!(f, "{name}({})", args.join(", "))
+            }
+            Expr::Convert(value, target) => write!(f, "{value} {CONVERT_KEYWORD} {target}"),
+            Expr::Uncertain(value, uncertainty) => write!(f, "{value} ± {uncertainty}"),
+            Expr::Date(date) => write!(f, "{}", dates::format_literal(*date)),
+            Expr::Duration(duration) => write!(f, "{}", duration.compact()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<Value, Error> {
+        evaluate(input, &Context::default())
+    }
+
+    fn number(input: &str) -> f64 {
+        eval(input).and_then(|value| value.number()).unwrap()
+    }
+
+    #[test]
+    fn subtraction_without_spaces() {
+        assert_eq!(
+            parse("2-3"),
+            Ok(Expr::Binary(
+                Operator::Subtract,
+                Box::new(Expr::Number(2.0)),
+                Box::new(Expr::Number(3.0))
+            ))
+        );
+        assert_eq!(number("2-3"), -1.0);
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(number("-2^2"), -4.0);
+        assert_eq!(number("2^3^2"), 512.0);
+        assert_eq!(number("1 + 2 * 3"), 7.0);
+        assert_eq!(number("2(3 + 4)"), 14.0);
+        assert_eq!(number("3!"), 6.0);
+    }
+
+    #[test]
+    fn exponent_needs_digits() {
+        assert_eq!(number("1e3"), 1000.0);
+        assert_eq!(number("2e"), 2.0 * std::f64::consts::E);
+    }
+
+    #[test]
+    fn factorial_limits() {
+        assert!(number("170!").is_finite());
+        assert_eq!(number("171!"), f64::INFINITY);
+        assert_eq!(number("1e12!"), f64::INFINITY);
+        assert_eq!(number("inf!"), f64::INFINITY);
+        assert!(matches!(eval("2.5!"), Err(Error::Domain(_))));
+        assert!(matches!(eval("(-1)!"), Err(Error::Domain(_))));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval("1 +"), Err(Error::UnexpectedEnd));
+        assert_eq!(eval("(1"), Err(Error::UnexpectedEnd));
+        assert_eq!(eval("1 $"), Err(Error::UnexpectedChar('$')));
+        assert_eq!(eval("x"), Err(Error::UnknownVariable("x".into())));
+        assert_eq!(eval("foo(1)"), Err(Error::UnknownFunction("foo".into())));
+    }
+
+    #[test]
+    fn integer_functions() {
+        assert_eq!(number("mod(-7, 3)"), 2.0);
+        assert_eq!(number("div(-7, 3)"), -3.0);
+        assert_eq!(number("mod(7, -3)"), -2.0);
+        assert!(matches!(eval("mod(1, 0)"), Err(Error::Domain(_))));
+        assert_eq!(number("gcd(12, 18)"), 6.0);
+        assert_eq!(number("lcm(4, 6)"), 12.0);
+        assert_eq!(number("round(2.345, 2)"), 2.35);
+        assert_eq!(number("round(1.5, 400)"), 1.5);
+        assert_eq!(number("round(1e300, 20)"), 1e300);
+        assert_eq!(number("round(1234.5, -400)"), 0.0);
+        assert!(matches!(eval("170.5!"), Err(Error::Domain(_))));
+        assert_eq!(number("floor(-1.5)"), -2.0);
+        assert_eq!(number("sign(-3)"), -1.0);
+        assert_eq!(number("nCr(52, 5)"), 2_598_960.0);
+        assert_eq!(number("nPr(5, 2)"), 20.0);
+        assert_eq!(number("nCr(3, 5)"), 0.0);
+        for _ in 0..20 {
+            let roll = number("rand(1, 6)");
+            assert!((1.0..=6.0).contains(&roll) && roll.fract() == 0.0);
+        }
+    }
+
+    #[test]
+    fn number_theory() {
+        let text = |input: &str| eval(input).unwrap().to_string();
+        assert_eq!(text("factor(600851475143)"), "71 · 839 · 1471 · 6857");
+        assert_eq!(number("isprime(97)"), 1.0);
+        assert_eq!(number("nextprime(13)"), 17.0);
+        assert_eq!(number("totient(36)"), 12.0);
+        assert_eq!(number("powmod(4, 13, 497)"), 445.0);
+        // Literals past 2^53 are read exactly rather than rounded to a double.
+        assert_eq!(number("isprime(18446744073709551557)"), 1.0);
+        assert_eq!(number("isprime(18446744073709551556)"), 0.0);
+        assert_eq!(
+            text("nextprime(18446744073709551556)"),
+            "18446744073709551557"
+        );
+        assert!(matches!(
+            eval("nextprime(18446744073709551557)"),
+            Err(Error::Domain(_))
+        ));
+        assert!(matches!(eval("factor(0)"), Err(Error::Domain(_))));
+        assert!(matches!(eval("factor(2.5)"), Err(Error::Domain(_))));
+    }
+
+    #[test]
+    fn variables() {
+        let mut context = Context::default();
+        context.set("x", 4.0);
+        assert_eq!(evaluate("x^2 + 1", &context), Ok(Value::Number(17.0)));
+    }
+}
@@ -0,0 +1,362 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Longest amortization schedule that will be generated.
+pub const MAX_PERIODS: usize = 1200;
+
+const TOLERANCE: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NoSolution(Register),
+    TooManyPeriods(f64),
+    NoCashFlows,
+    NoSignChange,
+    Io(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoSolution(register) => write!(f, "no solution for {register}"),
+            Error::TooManyPeriods(n) => write!(
+                f,
+                "schedule needs a whole number of periods between 1 and {MAX_PERIODS}, got {n}"
+            ),
+            Error::NoCashFlows => write!(f, "at least one cash flow is required"),
+            Error::NoSignChange => write!(f, "cash flows must change sign to have an IRR"),
+            Error::Io(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// The five time-value-of-money registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Register {
+    N,
+    Rate,
+    PresentValue,
+    Payment,
+    FutureValue,
+}
+
+impl Register {
+    pub const ALL: [Register; 5] = [
+        Register::N,
+        Register::Rate,
+        Register::PresentValue,
+        Register::Payment,
+        Register::FutureValue,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Register::N => "N",
+            Register::Rate => "I/Y",
+            Register::PresentValue => "PV",
+            Register::Payment => "PMT",
+            Register::FutureValue => "FV",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Whether payments fall at the beginning or the end of each period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Timing {
+    Begin,
+    #[default]
+    End,
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Timing::Begin => "BEGIN",
+            Timing::End => "END",
+        })
+    }
+}
+
+/// Register contents, using the cash-flow sign convention: money received is
+/// positive, money paid out is negative. The rate is a percentage per period.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tvm {
+    pub values: [f64; 5],
+    pub timing: Timing,
+}
+
+/// One row of an amortization schedule, with amounts signed like the payment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+    pub number: usize,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub balance: f64,
+}
+
+impl Tvm {
+    pub fn get(&self, register: Register) -> f64 {
+        self.values[register.index()]
+    }
+
+    pub fn set(&mut self, register: Register, value: f64) {
+        self.values[register.index()] = value;
+    }
+
+    /// Computes `register` from the other four and stores it.
+    pub fn solve(&mut self, register: Register) -> Result<f64, Error> {
+        let [n, rate, pv, pmt, fv] = self.values;
+        let i = rate / 100.0;
+        let timing = self.timing;
+        let value = match register {
+            Register::N => {
+                let pmt = pmt * timing.factor(i);
+                if i == 0.0 {
+                    -(pv + fv) / pmt
+                } else {
+                    ((pmt - fv * i) / (pmt + pv * i)).ln() / i.ln_1p()
+                }
+            }
+            Register::Rate => 100.0 * rate_root(|i| balance(n, i, pv, pmt, fv, timing), i)?,
+            Register::PresentValue => -(fv + pmt * timing.factor(i) * annuity(n, i)) / growth(n, i),
+            Register::Payment => -(pv * growth(n, i) + fv) / (timing.factor(i) * annuity(n, i)),
+            Register::FutureValue => -(pv * growth(n, i) + pmt * timing.factor(i) * annuity(n, i)),
+        };
+        if !value.is_finite() {
+            return Err(Error::NoSolution(register));
+        }
+        self.set(register, value);
+        Ok(value)
+    }
+
+    /// Splits each payment into interest and principal, starting from PV.
+    pub fn schedule(&self) -> Result<Vec<Period>, Error> {
+        let [n, rate, pv, pmt, _] = self.values;
+        // Solving for N leaves rounding noise on otherwise whole numbers of periods.
+        let periods = n.round();
+        if (n - periods).abs() > TOLERANCE || periods < 1.0 || periods > MAX_PERIODS as f64 {
+            return Err(Error::TooManyPeriods(n));
+        }
+        let i = rate / 100.0;
+        let paid_first = if self.timing == Timing::Begin {
+            pmt
+        } else {
+            0.0
+        };
+
+        let mut balance = pv;
+        let schedule = (1..=periods as usize)
+            .map(|number| {
+                let interest = -(balance + paid_first) * i;
+                let principal = pmt - interest;
+                balance += principal;
+                Period {
+                    number,
+                    payment: pmt,
+                    interest,
+                    principal,
+                    balance,
+                }
+            })
+            .collect();
+        Ok(schedule)
+    }
+}
+
+impl Timing {
+    /// Extra period of interest earned by payments made at the beginning.
+    fn factor(self, i: f64) -> f64 {
+        match self {
+            Timing::Begin => 1.0 + i,
+            Timing::End => 1.0,
+        }
+    }
+}
+
+/// `(1 + i)^n`.
+fn growth(n: f64, i: f64) -> f64 {
+    (n * i.ln_1p()).exp()
+}
+
+/// `((1 + i)^n − 1) / i`, the future value of n unit payments.
+fn annuity(n: f64, i: f64) -> f64 {
+    if i == 0.0 {
+        n
+    } else {
+        (n * i.ln_1p()).exp_m1() / i
+    }
+}
+
+/// What is left over once all five registers are accounted for; zero when they agree.
+fn balance(n: f64, i: f64, pv: f64, pmt: f64, fv: f64, timing: Timing) -> f64 {
+    pv * growth(n, i) + pmt * timing.factor(i) * annuity(n, i) + fv
+}
+
+/// Net present value of cash flows at times 0, 1, 2, … discounted at `rate` percent.
+pub fn npv(rate: f64, flows: &[f64]) -> Result<f64, Error> {
+    if flows.is_empty() {
+        return Err(Error::NoCashFlows);
+    }
+    Ok(present_value(rate / 100.0, flows))
+}
+
+/// Internal rate of return in percent: the rate at which the NPV is zero.
+pub fn irr(flows: &[f64]) -> Result<f64, Error> {
+    if flows.is_empty() {
+        return Err(Error::NoCashFlows);
+    }
+    if !(flows.iter().any(|&flow| flow > 0.0) && flows.iter().any(|&flow| flow < 0.0)) {
+        return Err(Error::NoSignChange);
+    }
+    rate_root(|i| present_value(i, flows), 0.1)
+        .map(|i| 100.0 * i)
+        .map_err(|_| Error::NoSignChange)
+}
+
+fn present_value(i: f64, flows: &[f64]) -> f64 {
+    flows
+        .iter()
+        .rev()
+        .fold(0.0, |sum, &flow| sum / (1.0 + i) + flow)
+}
+
+/// Finds a periodic rate above −100% where `f` changes sign, preferring the one closest to `guess`.
+fn rate_root(f: impl Fn(f64) -> f64, guess: f64) -> Result<f64, Error> {
+    const RATES: [f64; 20] = [
+        -0.99, -0.9, -0.5, -0.2, -0.1, -0.05, -0.01, 0.0, 0.001, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5,
+        1.0, 2.0, 5.0, 10.0, 100.0,
+    ];
+    let no_solution = Error::NoSolution(Register::Rate);
+
+    let bracket = RATES
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .filter(|&(a, b)| {
+            let (fa, fb) = (f(a), f(b));
+            (fa.is_finite() && fb.is_finite() && fa.signum() != fb.signum()) || fa == 0.0
+        })
+        .min_by(|x, y| {
+            let distance = |(a, b): (f64, f64)| (0.5 * (a + b) - guess).abs();
+            distance(*x).total_cmp(&distance(*y))
+        });
+    let Some((mut a, mut b)) = bracket else {
+        return Err(no_solution);
+    };
+
+    let mut fa = f(a);
+    if fa == 0.0 {
+        return Ok(a);
+    }
+    for _ in 0..MAX_ITERATIONS {
+        let middle = 0.5 * (a + b);
+        let fm = f(middle);
+        if fm == 0.0 || middle <= a || middle >= b {
+            return Ok(middle);
+        }
+        if fm.signum() == fa.signum() {
+            (a, fa) = (middle, fm);
+        } else {
+            b = middle;
+        }
+    }
+    Err(no_solution)
+}
+
+/// Writes the schedule as CSV with a header row.
+pub fn export_schedule(schedule: &[Period], path: &Path) -> Result<(), Error> {
+    let mut csv = String::from("period,payment,interest,principal,balance\n");
+    for period in schedule {
+        csv.push_str(&format!(
+            "{},{:.2},{:.2},{:.2},{:.2}\n",
+            period.number, period.payment, period.interest, period.principal, period.balance
+        ));
+    }
+    std::fs::write(path, csv)
+        .map_err(|error| Error::Io(format!("cannot write {}: {error}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-6 * b.abs().max(1.0)
+    }
+
+    fn mortgage() -> Tvm {
+        Tvm {
+            values: [360.0, 0.5, 200_000.0, 0.0, 0.0],
+            timing: Timing::End,
+        }
+    }
+
+    #[test]
+    fn payment() {
+        let mut tvm = mortgage();
+        let payment = tvm.solve(Register::Payment).unwrap();
+        assert!((payment - -1199.101050).abs() < 1e-6);
+    }
+
+    #[test]
+    fn solving_back_gives_the_inputs() {
+        let mut tvm = mortgage();
+        tvm.solve(Register::Payment).unwrap();
+        for register in [
+            Register::N,
+            Register::Rate,
+            Register::PresentValue,
+            Register::FutureValue,
+        ] {
+            let expected = tvm.get(register);
+            assert!(close(tvm.solve(register).unwrap(), expected), "{register}");
+        }
+    }
+
+    #[test]
+    fn begin_mode_pays_less() {
+        let mut end = mortgage();
+        let mut begin = Tvm {
+            timing: Timing::Begin,
+            ..mortgage()
+        };
+        let end = end.solve(Register::Payment).unwrap();
+        let begin = begin.solve(Register::Payment).unwrap();
+        assert!(close(begin * 1.005, end));
+    }
+
+    #[test]
+    fn schedule_pays_off_the_loan() {
+        let mut tvm = mortgage();
+        tvm.solve(Register::Payment).unwrap();
+        let schedule = tvm.schedule().unwrap();
+        assert_eq!(schedule.len(), 360);
+        assert!(close(schedule[0].interest, -1000.0));
+        assert!(schedule[359].balance.abs() < 1e-6);
+    }
+
+    #[test]
+    fn npv_and_irr() {
+        assert!(close(npv(10.0, &[-100.0, 110.0]).unwrap(), 0.0));
+        assert!(close(irr(&[-100.0, 110.0]).unwrap(), 10.0));
+        assert!(close(
+            irr(&[-1000.0, 300.0, 400.0, 500.0]).unwrap(),
+            8.896_339_469
+        ));
+        assert_eq!(irr(&[100.0, 110.0]), Err(Error::NoSignChange));
+        assert_eq!(npv(5.0, &[]), Err(Error::NoCashFlows));
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+/// Largest denominator tried when approximating a fraction.
+const MAX_DENOMINATOR: u128 = 1_000_000;
+
+/// Digits after the radix point before an expansion is cut off.
+const MAX_FRACTION_DIGITS: usize = 64;
+
+/// Alternative ways of writing a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Hexadecimal,
+    Binary,
+    Octal,
+    Scientific,
+    Fraction,
+    Ieee754,
+}
+
+impl Format {
+    pub const ALL: [Format; 6] = [
+        Format::Hexadecimal,
+        Format::Binary,
+        Format::Octal,
+        Format::Scientific,
+        Format::Fraction,
+        Format::Ieee754,
+    ];
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Hexadecimal => "hex",
+            Format::Binary => "bin",
+            Format::Octal => "oct",
+            Format::Scientific => "sci",
+            Format::Fraction => "frac",
+            Format::Ieee754 => "IEEE 754",
+        })
+    }
+}
+
+/// A value written in one format; `exact` is false for approximations and cut-off expansions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Representation {
+    pub format: Format,
+    pub text: String,
+    pub exact: bool,
+}
+
+/// Every format that can express `value`.
+pub fn representations(value: f64) -> Vec<Representation> {
+    Format::ALL
+        .into_iter()
+        .filter_map(|format| {
+            let (text, exact) = match format {
+                Format::Hexadecimal => radix(value, 16, "0x")?,
+                Format::Binary => radix(value, 2, "0b")?,
+                Format::Octal => radix(value, 8, "0o")?,
+                Format::Scientific => (format!("{value:e}"), true),
+                Format::Fraction => fraction(value)?,
+                Format::Ieee754 => (ieee754(value), true),
+            };
+            Some(Representation {
+                format,
+                text,
+                exact,
+            })
+        })
+        .collect()
+}
+
+/// Positional notation in a power-of-two base, which every finite double expands to exactly.
+fn radix(value: f64, base: u32, prefix: &str) -> Option<(String, bool)> {
+    let magnitude = value.abs();
+    // Too large for the integer part, or too small to show a single digit.
+    let smallest = (base as f64).powi(-(MAX_FRACTION_DIGITS as i32));
+    let too_small = value != 0.0 && magnitude < smallest;
+    if !value.is_finite() || magnitude >= u128::MAX as f64 || too_small {
+        return None;
+    }
+    let mut text = String::new();
+    if value < 0.0 {
+        text.push('-');
+    }
+    text.push_str(prefix);
+
+    let mut integer = magnitude.trunc() as u128;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(digit((integer % base as u128) as u32, base)?);
+        integer /= base as u128;
+        if integer == 0 {
+            break;
+        }
+    }
+    text.extend(digits.iter().rev());
+
+    let mut fraction = magnitude.fract();
+    if fraction > 0.0 {
+        text.push('.');
+        for _ in 0..MAX_FRACTION_DIGITS {
+            if fraction == 0.0 {
+                break;
+            }
+            // Multiplying by a power of two is exact, so no rounding creeps in.
+            fraction *= base as f64;
+            text.push(digit(fraction.trunc() as u32, base)?);
+            fraction = fraction.fract();
+        }
+    }
+    Some((text, fraction == 0.0))
+}
+
+fn digit(value: u32, base: u32) -> Option<char> {
+    char::from_digit(value, base).map(|c| c.to_ascii_uppercase())
+}
+
+/// Closest fraction with a denominator up to `MAX_DENOMINATOR`, from the continued fraction.
+fn fraction(value: f64) -> Option<(String, bool)> {
+    // Whole numbers gain nothing from being written over 1.
+    if !value.is_finite() || value.fract() == 0.0 {
+        return None;
+    }
+    let magnitude = value.abs();
+    let (mut p0, mut p1, mut q0, mut q1) = (0u128, 1u128, 1u128, 0u128);
+    let mut x = magnitude;
+    loop {
+        let a = x.floor();
+        // Past the first term the denominator grows at least by a factor of a.
+        if q1 > 0 && a > MAX_DENOMINATOR as f64 {
+            break;
+        }
+        let a = a as u128;
+        let (p, q) = (a * p1 + p0, a * q1 + q0);
+        if q > MAX_DENOMINATOR {
+            break;
+        }
+        (p0, p1, q0, q1) = (p1, p, q1, q);
+        let rest = x - x.floor();
+        if rest == 0.0 {
+            break;
+        }
+        x = 1.0 / rest;
+    }
+
+    if p1 == 0 {
+        return None;
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    let exact = p1 as f64 / q1 as f64 == magnitude;
+    Some((format!("{sign}{p1}/{q1}"), exact))
+}
+
+/// Sign, exponent and mantissa bits of the double.
+fn ieee754(value: f64) -> String {
+    let bits = value.to_bits();
+    format!(
+        "{} {:011b} {:052b}",
+        bits >> 63,
+        (bits >> 52) & 0x7ff,
+        bits & ((1 << 52) - 1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: f64, format: Format) -> Option<(String, bool)> {
+        representations(value)
+            .into_iter()
+            .find(|representation| representation.format == format)
+            .map(|representation| (representation.text, representation.exact))
+    }
+
+    #[test]
+    fn radix() {
+        assert_eq!(
+            text(255.0, Format::Hexadecimal),
+            Some(("0xFF".into(), true))
+        );
+        assert_eq!(
+            text(-10.5, Format::Binary),
+            Some(("-0b1010.1".into(), true))
+        );
+        assert_eq!(text(8.0, Format::Octal), Some(("0o10".into(), true)));
+        assert_eq!(text(f64::INFINITY, Format::Hexadecimal), None);
+        // The last bits of 1e-10 lie past the 64 digits shown.
+        assert_eq!(
+            text(1e-10, Format::Binary).map(|(_, exact)| exact),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn fraction() {
+        assert_eq!(text(0.75, Format::Fraction), Some(("3/4".into(), true)));
+        assert_eq!(text(-1.5, Format::Fraction), Some(("-3/2".into(), true)));
+        assert_eq!(
+            text(std::f64::consts::PI, Format::Fraction),
+            Some(("1146408/364913".into(), false))
+        );
+        assert_eq!(text(2.0, Format::Fraction), None);
+    }
+
+    #[test]
+    fn ieee754() {
+        assert_eq!(
+            text(1.0, Format::Ieee754),
+            Some((format!("0 01111111111 {}", "0".repeat(52)), true))
+        );
+    }
+}
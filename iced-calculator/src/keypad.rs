@@ -0,0 +1,229 @@
+use crate::expr;
+use crate::{LogFunction, Message, Operator, TrigFunction};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The bundled layout, used when there is no keypad file.
+const BUNDLED: &str = include_str!("keypad.toml");
+
+/// The variable a key's function is applied to.
+pub const ARGUMENT: &str = "x";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Io(String),
+    Parse(String),
+    InvalidWidth(String),
+    InvalidFunction { label: String, error: expr::Error },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(message) => write!(f, "{message}"),
+            Error::Parse(message) => write!(f, "invalid keypad file: {message}"),
+            Error::InvalidWidth(label) => write!(f, "key '{label}' must be at least one cell wide"),
+            Error::InvalidFunction { label, error } => {
+                write!(f, "function of key '{label}': {error}")
+            }
+        }
+    }
+}
+
+/// The three keypads, each a list of rows of keys.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Layout {
+    pub integer: Vec<Vec<Key>>,
+    pub scientific: Vec<Vec<Key>>,
+    pub basic: Vec<Vec<Key>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Key {
+    #[serde(default)]
+    pub label: String,
+    /// Width in keypad cells.
+    #[serde(default = "one")]
+    pub width: u16,
+    #[serde(flatten)]
+    pub binding: Binding,
+}
+
+fn one() -> u16 {
+    1
+}
+
+/// What pressing a key does.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Binding {
+    /// A built-in key.
+    Action(Action),
+    /// Types the snippet into the input.
+    Insert(String),
+    /// Evaluates the expression with the input as `x`.
+    Function(String),
+}
+
+/// The built-in keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Clear,
+    Calculate,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Percentage,
+    Sin,
+    Cos,
+    Tan,
+    Log,
+    Ln,
+    SquareRoot,
+    RootY,
+    Factorial,
+    Square,
+    Cube,
+    Reciprocal,
+    Exponential,
+    Euler,
+    Ee,
+    Negate,
+    Pi,
+    AngleMode,
+}
+
+impl Action {
+    fn message(self) -> Message {
+        match self {
+            Action::Clear => Message::Clear,
+            Action::Calculate => Message::Calculate,
+            Action::Add => Message::OperatorPressed(Operator::Add),
+            Action::Subtract => Message::OperatorPressed(Operator::Subtract),
+            Action::Multiply => Message::OperatorPressed(Operator::Multiply),
+            Action::Divide => Message::OperatorPressed(Operator::Divide),
+            Action::Power => Message::Exponentiate,
+            Action::Percentage => Message::Percentage,
+            Action::Sin => Message::TrigFunctionPressed(TrigFunction::Sine),
+            Action::Cos => Message::TrigFunctionPressed(TrigFunction::Cosine),
+            Action::Tan => Message::TrigFunctionPressed(TrigFunction::Tangent),
+            Action::Log => Message::LogFunctionPressed(LogFunction::Log10),
+            Action::Ln => Message::LogFunctionPressed(LogFunction::Ln),
+            Action::SquareRoot => Message::SquareRoot,
+            Action::RootY => Message::RootY,
+            Action::Factorial => Message::Factorial,
+            Action::Square => Message::Square,
+            Action::Cube => Message::Cube,
+            Action::Reciprocal => Message::Reciprocal,
+            Action::Exponential => Message::Exponential,
+            Action::Euler => Message::Euler,
+            Action::Ee => Message::EE,
+            Action::Negate => Message::Negate,
+            Action::Pi => Message::Input("π".into()),
+            Action::AngleMode => Message::ToggleAngleMode,
+        }
+    }
+}
+
+impl Key {
+    pub fn message(&self) -> Message {
+        match &self.binding {
+            Binding::Action(action) => action.message(),
+            Binding::Insert(snippet) => Message::Input(snippet.clone()),
+            Binding::Function(function) => Message::ApplyFunction(function.clone()),
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::parse(BUNDLED).expect("the bundled keypad layout is valid")
+    }
+}
+
+impl Layout {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let layout: Layout =
+            toml::from_str(text).map_err(|error| Error::Parse(error.to_string()))?;
+        for key in [&layout.integer, &layout.scientific, &layout.basic]
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            if key.width == 0 {
+                return Err(Error::InvalidWidth(key.label.clone()));
+            }
+            if let Binding::Function(function) = &key.binding {
+                expr::parse(function).map_err(|error| Error::InvalidFunction {
+                    label: key.label.clone(),
+                    error,
+                })?;
+            }
+        }
+        Ok(layout)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| Error::Io(format!("cannot read {}: {error}", path.display())))?;
+        Self::parse(&text)
+    }
+}
+
+/// Where a custom keypad layout is looked for.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("iced-calculator").join("keypad.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_layout() {
+        let layout = Layout::default();
+        assert_eq!(layout.basic.len(), 5);
+        assert_eq!(layout.basic[4][0].binding, Binding::Insert("0".into()));
+        assert_eq!(layout.basic[4][0].width, 2);
+    }
+
+    #[test]
+    fn custom_keys() {
+        let layout = Layout::parse(
+            r#"
+            integer = []
+            scientific = []
+            basic = [[
+                { label = "sin", action = "sin" },
+                { label = "x²+1", function = "x^2 + 1" },
+            ]]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(layout.basic[0][0].binding, Binding::Action(Action::Sin));
+        assert_eq!(layout.basic[0][1].width, 1);
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let layout =
+            |key: &str| Layout::parse(&format!("integer = []\nscientific = []\nbasic = [[{key}]]"));
+        assert_eq!(
+            layout(r#"{ label = "w", insert = "1", width = 0 }"#).unwrap_err(),
+            Error::InvalidWidth("w".into())
+        );
+        assert!(matches!(
+            layout(r#"{ label = "f", function = "x +" }"#),
+            Err(Error::InvalidFunction { .. })
+        ));
+        assert!(matches!(
+            layout(r#"{ label = "?", action = "teleport" }"#),
+            Err(Error::Parse(_))
+        ));
+    }
+}
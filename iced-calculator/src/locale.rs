@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Separator { found: char, locale: Locale },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Separator { found, locale } => write!(
+                f,
+                "unexpected '{found}': use '{}' for decimals and '{}' between arguments",
+                locale.decimal(),
+                locale.separator()
+            ),
+        }
+    }
+}
+
+/// How numbers are written and read back: decimal separator and digit grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Locale {
+    /// Exactly what the evaluator reads, without grouping.
+    #[default]
+    Plain,
+    English,
+    German,
+    French,
+    Swiss,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 5] = [
+        Locale::Plain,
+        Locale::English,
+        Locale::German,
+        Locale::French,
+        Locale::Swiss,
+    ];
+
+    pub fn decimal(self) -> char {
+        match self {
+            Locale::Plain | Locale::English | Locale::Swiss => '.',
+            Locale::German | Locale::French => ',',
+        }
+    }
+
+    pub fn grouping(self) -> Option<char> {
+        match self {
+            Locale::Plain => None,
+            Locale::English => Some(','),
+            Locale::German => Some('.'),
+            Locale::French => Some(' '),
+            Locale::Swiss => Some('\''),
+        }
+    }
+
+    /// Separates function arguments; a semicolon wherever the comma is the decimal separator.
+    pub fn separator(self) -> char {
+        if self.decimal() == ',' {
+            ';'
+        } else {
+            ','
+        }
+    }
+
+    /// Keypad label for the decimal separator.
+    pub fn decimal_label(self) -> &'static str {
+        if self.decimal() == ',' {
+            ","
+        } else {
+            "."
+        }
+    }
+
+    /// Keypad label for the argument separator.
+    pub fn separator_label(self) -> &'static str {
+        if self.separator() == ';' {
+            ";"
+        } else {
+            ","
+        }
+    }
+
+    /// Turns evaluator syntax, such as a keypad snippet, into what the user would type.
+    pub fn localize(self, text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '.' => self.decimal(),
+                ',' => self.separator(),
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Turns what the user typed into evaluator syntax.
+    ///
+    /// Grouping separators are only accepted before exactly three digits, and never
+    /// when they double as the argument separator, so every input has one reading.
+    pub fn delocalize(self, text: &str) -> Result<String, Error> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut canonical = String::with_capacity(text.len());
+        for (i, &c) in chars.iter().enumerate() {
+            let groups = Some(c) == self.grouping()
+                && c != self.separator()
+                && i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars.len() >= i + 4
+                && chars[i + 1..i + 4].iter().all(char::is_ascii_digit)
+                && !chars.get(i + 4).is_some_and(char::is_ascii_digit);
+            if groups {
+                continue;
+            }
+            match c {
+                c if c == self.decimal() => canonical.push('.'),
+                c if c == self.separator() => canonical.push(','),
+                '.' | ',' => {
+                    return Err(Error::Separator {
+                        found: c,
+                        locale: self,
+                    })
+                }
+                c => canonical.push(c),
+            }
+        }
+        Ok(canonical)
+    }
+
+    /// Rewrites text typed for this locale with the separators of `to`; text that does
+    /// not read back is left as it is.
+    pub fn relocalize(self, text: &str, to: Locale) -> String {
+        match self.delocalize(text) {
+            Ok(canonical) => to.localize(&canonical),
+            Err(_) => text.to_string(),
+        }
+    }
+
+    /// Formats a result for display, grouping the digits of every number.
+    pub fn display(self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut shown = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let previous = i.checked_sub(1).map(|j| chars[j]);
+            let next = chars.get(i + 1).copied();
+            // Digits after a letter or a decimal point are names, exponents or fractions.
+            let integer_part = c.is_ascii_digit()
+                && !previous.is_some_and(|p| p.is_alphanumeric() || p == '_' || p == '.');
+            if integer_part {
+                let end = (i..chars.len())
+                    .find(|&j| !chars[j].is_ascii_digit())
+                    .unwrap_or(chars.len());
+                self.group(&chars[i..end], &mut shown);
+                i = end;
+                continue;
+            }
+            match c {
+                '.' if next.is_some_and(|n| n.is_ascii_digit()) => shown.push(self.decimal()),
+                // Only commas between numbers, as in `[1, -2]`, not those in messages.
+                ',' if previous.is_some_and(|p| p.is_ascii_digit())
+                    && next == Some(' ')
+                    && chars
+                        .get(i + 2)
+                        .is_some_and(|&n| n.is_ascii_digit() || n == '-') =>
+                {
+                    shown.push(self.separator())
+                }
+                c => shown.push(c),
+            }
+            i += 1;
+        }
+        shown
+    }
+
+    /// Four-digit numbers stay together, as SI style recommends, so years read naturally.
+    fn group(self, digits: &[char], shown: &mut String) {
+        for (k, &digit) in digits.iter().enumerate() {
+            if let Some(grouping) = self.grouping() {
+                if k > 0 && digits.len() > 4 && (digits.len() - k).is_multiple_of(3) {
+                    shown.push(grouping);
+                }
+            }
+            shown.push(digit);
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Locale::Plain => "1234567.89",
+            Locale::English => "1,234,567.89",
+            Locale::German => "1.234.567,89",
+            Locale::French => "1 234 567,89",
+            Locale::Swiss => "1'234'567.89",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delocalize() {
+        assert_eq!(Locale::German.delocalize("1.234,5"), Ok("1234.5".into()));
+        assert_eq!(
+            Locale::German.delocalize("max(1,5; 2)"),
+            Ok("max(1.5, 2)".into())
+        );
+        assert_eq!(
+            Locale::Swiss.delocalize("1'234'567.8"),
+            Ok("1234567.8".into())
+        );
+        assert_eq!(Locale::French.delocalize("1 234,5"), Ok("1234.5".into()));
+        // In English the comma separates arguments, so it is never read as grouping.
+        assert_eq!(
+            Locale::English.delocalize("max(1,234)"),
+            Ok("max(1,234)".into())
+        );
+        assert_eq!(
+            Locale::German.delocalize("1.5"),
+            Err(Error::Separator {
+                found: '.',
+                locale: Locale::German
+            })
+        );
+        assert_eq!(Locale::Plain.delocalize("1.5, 2"), Ok("1.5, 2".into()));
+    }
+
+    #[test]
+    fn localize_and_display() {
+        assert_eq!(Locale::German.localize("max(1.5, 2)"), "max(1,5; 2)");
+        assert_eq!(Locale::German.display("1234567.89"), "1.234.567,89");
+        assert_eq!(Locale::French.display("2026"), "2026");
+        assert_eq!(Locale::English.display("x2 = 12345"), "x2 = 12,345");
+        assert_eq!(Locale::English.display("[1, -2]"), "[1, -2]");
+        assert_eq!(Locale::German.display("[1, -2]"), "[1; -2]");
+        assert_eq!(Locale::German.relocalize("2,5", Locale::English), "2.5");
+        assert_eq!(Locale::German.relocalize("2.5", Locale::English), "2.5");
+    }
+}
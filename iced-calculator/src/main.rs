@@ -1,11 +1,44 @@
+mod calculus;
+mod constants;
+mod currency;
+mod dates;
+mod expr;
+mod finance;
+mod formats;
+mod keypad;
+mod locale;
+mod matrix;
+mod number_theory;
+mod polynomial;
+mod script;
+mod significant;
+mod sizing;
+mod solver;
+mod typeset;
+mod uncertainty;
+mod units;
+mod worksheet;
+
 use iced::{
-    widget::{button, column, container, row, text, text_input, Button, Column},
-    Center, Element, Fill, Font, Task, Theme,
+    widget::{
+        button, canvas, checkbox, column, container, pick_list, row, scrollable, text, text_editor,
+        text_input, Button, Column, Row,
+    },
+    window, Center, Element, Fill, Font, Right, Size, Subscription, Task, Theme,
 };
-use std::f64::consts::{E, PI};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::E;
+use std::fmt;
 
 fn main() -> iced::Result {
+    if let [_, flag, path] = std::env::args().collect::<Vec<_>>().as_slice() {
+        if flag == "--replay" {
+            let passed = script::run_headless(std::path::Path::new(path));
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+    }
     iced::application("Calculator v2 - Iced", Calculator::update, Calculator::view)
+        .subscription(Calculator::subscription)
         .theme(|_| Theme::Dark)
         .default_font(Font::MONOSPACE)
         .run_with(Calculator::new)
@@ -15,13 +48,118 @@ struct Calculator {
     input: String,
     result: String,
     operator: Option<Operator>,
-    operand: Option<f64>,
+    operand: Option<expr::Value>,
     angle_mode: AngleMode,
+    mode: Mode,
+    solve_start: String,
+    matrices: [matrix::Editor; 3],
+    selected_matrix: usize,
+    conversion_category: units::Category,
+    conversion_from: String,
+    conversion_to: String,
+    rates: currency::Editor,
+    /// Where the rate table is kept; `None` when replaying headlessly, so that a
+    /// script cannot overwrite the user's rates.
+    rates_file: Option<std::path::PathBuf>,
+    rates_path: String,
+    rates_max_age: String,
+    rates_status: String,
+    tvm: finance::Tvm,
+    schedule: Vec<finance::Period>,
+    schedule_path: String,
+    schedule_status: String,
+    propagation: uncertainty::Propagation,
+    polynomial: polynomial::Editor,
+    roots: Vec<polynomial::Complex>,
+    worksheet: text_editor::Content,
+    worksheet_results: Vec<String>,
+    worksheet_path: String,
+    worksheet_status: String,
+    show_formats: bool,
+    locale: locale::Locale,
+    keypad: keypad::Layout,
+    sizing: sizing::Sizing,
+    drawer_open: bool,
+    show_history: bool,
+    history: Vec<(String, String)>,
+    show_constants: bool,
+    constant_search: String,
+    /// The EE key was pressed and the digits typed go into the exponent.
+    entering_exponent: bool,
+    significant_figures: bool,
+    /// Significant figures of the pending operand, for chained operations.
+    operand_figures: Option<u32>,
+    /// Messages recorded since recording started.
+    recording: Option<Vec<Message>>,
+    script_path: String,
+    script_status: String,
 }
 
-#[derive(Debug, Clone)]
+const MATRIX_NAMES: [&str; 3] = ["A", "B", "C"];
+
+/// Calculations kept in the history panel.
+const HISTORY_LENGTH: usize = 100;
+
+/// Characters after which a minus sign is a negation rather than a subtraction.
+const OPERAND_FOLLOWS: &str = "+-−*×·/÷^(,;±";
+
+/// Everything the user can do; recorded and replayed by [`script`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum Message {
     Input(String),
+    InputChanged(String),
+    ModeSelected(Mode),
+    SolveStartChanged(String),
+    MatrixSelected(usize),
+    MatrixResized(usize, usize),
+    MatrixCellChanged(usize, usize, String),
+    ConversionCategorySelected(units::Category),
+    ConversionFromSelected(String),
+    ConversionToSelected(String),
+    SwapConversionUnits,
+    RateBaseChanged(String),
+    RateDateChanged(String),
+    RateCodeChanged(usize, String),
+    RateValueChanged(usize, String),
+    RateAdded,
+    RateRemoved(usize),
+    RatesMaxAgeChanged(String),
+    RatesPathChanged(String),
+    RatesImported,
+    RatesSaved,
+    RegisterPressed(finance::Register),
+    ToggleTiming,
+    ClearRegisters,
+    Amortize,
+    SchedulePathChanged(String),
+    ScheduleExported,
+    PropagationSelected(uncertainty::Propagation),
+    DegreeChanged(usize),
+    CoefficientChanged(usize, String),
+    FindRoots,
+    #[serde(skip)]
+    WorksheetEdited(text_editor::Action),
+    WorksheetPathChanged(String),
+    WorksheetSaved,
+    WorksheetOpened,
+    FormatsToggled(bool),
+    ApplyFunction(String),
+    #[serde(skip)]
+    WindowResized(Size),
+    ToggleDrawer,
+    HistoryToggled(bool),
+    LocaleSelected(locale::Locale),
+    ConstantsToggled(bool),
+    ConstantSearchChanged(String),
+    SignificantFiguresToggled(bool),
+    #[serde(skip)]
+    ScriptPathChanged(String),
+    #[serde(skip)]
+    ToggleRecording,
+    #[serde(skip)]
+    ReplayScript,
+    CopyToClipboard(String),
     Clear,
     Calculate,
     OperatorPressed(Operator),
@@ -37,11 +175,14 @@ enum Message {
     RootY,
     Exponential,
     Euler,
+    #[serde(rename = "ee")]
     EE,
+    Negate,
     Percentage,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum Operator {
     Add,
     Subtract,
@@ -50,14 +191,16 @@ enum Operator {
     Exponentiate,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum TrigFunction {
     Sine,
     Cosine,
     Tangent,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum LogFunction {
     Log10,
     Ln,
@@ -70,84 +213,453 @@ enum AngleMode {
     Radians,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Mode {
+    #[default]
+    Standard,
+    Solve,
+    Matrix,
+    Units,
+    Currency,
+    Finance,
+    Uncertainty,
+    Polynomial,
+    Worksheet,
+}
+
+impl Mode {
+    const ALL: [Mode; 9] = [
+        Mode::Standard,
+        Mode::Solve,
+        Mode::Matrix,
+        Mode::Units,
+        Mode::Currency,
+        Mode::Finance,
+        Mode::Uncertainty,
+        Mode::Polynomial,
+        Mode::Worksheet,
+    ];
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Mode::Standard => "Standard",
+            Mode::Solve => "Solve",
+            Mode::Matrix => "Matrix",
+            Mode::Units => "Units",
+            Mode::Currency => "Currency",
+            Mode::Finance => "Finance",
+            Mode::Uncertainty => "Uncertainty",
+            Mode::Polynomial => "Polynomial",
+            Mode::Worksheet => "Worksheet",
+        })
+    }
+}
+
+impl Operator {
+    fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide => lhs / rhs,
+            Operator::Exponentiate => lhs.powf(rhs),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Subtract => "−",
+            Operator::Multiply => "×",
+            Operator::Divide => "÷",
+            Operator::Exponentiate => "^",
+        }
+    }
+}
+
+impl TrigFunction {
+    /// The function's name in expressions.
+    fn name(self) -> &'static str {
+        match self {
+            TrigFunction::Sine => "sin",
+            TrigFunction::Cosine => "cos",
+            TrigFunction::Tangent => "tan",
+        }
+    }
+
+    fn apply(self, angle: f64, angle_mode: AngleMode) -> f64 {
+        // On the axes the result is exact, rather than off by the rounding of π/180.
+        if angle_mode == AngleMode::Degrees && angle % 90.0 == 0.0 {
+            let quadrant = (angle / 90.0).rem_euclid(4.0) as usize;
+            let (sin, cos) = [(0.0, 1.0), (1.0, 0.0), (0.0, -1.0), (-1.0, 0.0)][quadrant];
+            return match self {
+                TrigFunction::Sine => sin,
+                TrigFunction::Cosine => cos,
+                TrigFunction::Tangent => sin / cos,
+            };
+        }
+        let angle_radians = if angle_mode == AngleMode::Degrees {
+            angle.to_radians()
+        } else {
+            angle
+        };
+        match self {
+            TrigFunction::Sine => angle_radians.sin(),
+            TrigFunction::Cosine => angle_radians.cos(),
+            TrigFunction::Tangent => angle_radians.tan(),
+        }
+    }
+}
+
+impl LogFunction {
+    /// The function's name in expressions.
+    fn name(self) -> &'static str {
+        match self {
+            LogFunction::Log10 => "log",
+            LogFunction::Ln => "ln",
+        }
+    }
+
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            LogFunction::Log10 => value.log10(),
+            LogFunction::Ln => value.ln(),
+        }
+    }
+}
+
 impl Calculator {
     fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                input: String::new(),
-                result: String::new(),
-                operator: None,
-                operand: None,
-                angle_mode: AngleMode::Radians,
+        let mut calculator = Self::with_defaults();
+        calculator.rates_file = currency::default_path();
+        match &calculator.rates_file {
+            Some(path) if path.exists() => match currency::Table::load(path) {
+                Ok(table) => calculator.rates = currency::Editor::new(&table, calculator.locale),
+                Err(error) => calculator.rates_status = format!("Error: {error}"),
             },
-            Task::none(),
+            _ => {
+                calculator.rates_status =
+                    "No rate table yet: import a JSON or CSV file, or add rates and save".into()
+            }
+        }
+        if let Some(path) = keypad::default_path().filter(|path| path.exists()) {
+            match keypad::Layout::load(&path) {
+                Ok(layout) => calculator.keypad = layout,
+                Err(error) => calculator.result = format!("Error: {error}"),
+            }
+        }
+
+        (
+            calculator,
+            window::get_latest()
+                .and_then(window::get_size)
+                .map(Message::WindowResized),
         )
     }
 
+    /// A calculator with the bundled keypad and no rate table, ignoring the user's
+    /// configuration, so that a replayed script gives the same result on any machine.
+    fn with_defaults() -> Self {
+        Self {
+            input: String::new(),
+            result: String::new(),
+            operator: None,
+            operand: None,
+            angle_mode: AngleMode::Radians,
+            mode: Mode::Standard,
+            solve_start: String::new(),
+            matrices: Default::default(),
+            selected_matrix: 0,
+            conversion_category: units::Category::Length,
+            conversion_from: "km".into(),
+            conversion_to: "mi".into(),
+            rates: currency::Editor::default(),
+            rates_file: None,
+            rates_path: String::new(),
+            rates_max_age: currency::DEFAULT_MAX_AGE_DAYS.to_string(),
+            rates_status: String::new(),
+            tvm: finance::Tvm::default(),
+            schedule: Vec::new(),
+            schedule_path: "amortization.csv".into(),
+            schedule_status: String::new(),
+            propagation: uncertainty::Propagation::default(),
+            polynomial: polynomial::Editor::default(),
+            roots: Vec::new(),
+            worksheet: text_editor::Content::new(),
+            worksheet_results: Vec::new(),
+            worksheet_path: String::new(),
+            worksheet_status: String::new(),
+            show_formats: false,
+            locale: locale::Locale::default(),
+            keypad: keypad::Layout::default(),
+            sizing: sizing::Sizing::default(),
+            drawer_open: false,
+            show_history: true,
+            history: Vec::new(),
+            show_constants: false,
+            constant_search: String::new(),
+            entering_exponent: false,
+            significant_figures: false,
+            operand_figures: None,
+            recording: None,
+            script_path: String::new(),
+            script_status: String::new(),
+        }
+    }
+
     fn update(&mut self, message: Message) -> Task<Message> {
+        if let Some(recording) = &mut self.recording {
+            if script::is_recordable(&message) {
+                recording.push(message.clone());
+            }
+        }
+        if self.entering_exponent && self.enter_exponent(&message) {
+            return Task::none();
+        }
         match message {
-            Message::Input(value) => self.input.push_str(&value),
-            Message::Clear => self.clear(),
-            Message::Calculate => self.calculate(),
-            Message::OperatorPressed(op) => self.handle_operator(op),
-            Message::TrigFunctionPressed(trig_fn) => self.apply_trig_function(trig_fn),
-            Message::LogFunctionPressed(log_fn) => self.apply_log_function(log_fn),
-            Message::Exponentiate => self.prepare_exponentiation(),
-            Message::SquareRoot => self.apply_root(2.0),
-            Message::ToggleAngleMode => self.toggle_angle_mode(),
-            Message::Factorial => {
-                if let Ok(n) = self.input.parse::<u64>() {
-                    self.result = Self::factorial(n).to_string();
-                    self.input.clear();
+            Message::Input(value) if self.mode == Mode::Worksheet => {
+                self.edit_worksheet(text_editor::Action::Edit(text_editor::Edit::Paste(
+                    self.locale.localize(&value).into(),
+                )))
+            }
+            Message::Negate if self.mode == Mode::Worksheet => {
+                self.edit_worksheet(text_editor::Action::Edit(text_editor::Edit::Insert('-')))
+            }
+            Message::Input(value) => self.input.push_str(&self.locale.localize(&value)),
+            Message::InputChanged(value) => {
+                self.input = value;
+                self.entering_exponent &= self.exponent().is_some();
+            }
+            Message::ModeSelected(mode) => {
+                self.clear();
+                self.mode = mode;
+                self.refresh_worksheet();
+            }
+            Message::SolveStartChanged(value) => self.solve_start = value,
+            // Scripts are written by hand, so indexes from messages are checked.
+            Message::MatrixSelected(index) => {
+                if index < MATRIX_NAMES.len() {
+                    self.selected_matrix = index
                 }
             }
-            Message::Square => {
-                if let Ok(n) = self.input.parse::<f64>() {
-                    self.result = (n * n).to_string();
-                    self.input.clear();
+            Message::MatrixResized(rows, cols) => {
+                self.matrices[self.selected_matrix].resize(rows, cols)
+            }
+            Message::MatrixCellChanged(row, col, value) => {
+                if let Some(cell) = self.matrices[self.selected_matrix]
+                    .cells
+                    .get_mut(row)
+                    .and_then(|cells| cells.get_mut(col))
+                {
+                    *cell = value
                 }
             }
-            Message::Cube => {
-                if let Ok(n) = self.input.parse::<f64>() {
-                    self.result = (n * n * n).to_string();
-                    self.input.clear();
+            Message::ConversionCategorySelected(category) => {
+                let units = category.units();
+                self.conversion_category = category;
+                self.conversion_from = units[0].clone();
+                self.conversion_to = units[1 % units.len()].clone();
+            }
+            Message::ConversionFromSelected(unit) => self.conversion_from = unit,
+            Message::ConversionToSelected(unit) => self.conversion_to = unit,
+            Message::SwapConversionUnits => {
+                std::mem::swap(&mut self.conversion_from, &mut self.conversion_to)
+            }
+            Message::RateBaseChanged(base) => self.rates.base = base,
+            Message::RateDateChanged(date) => self.rates.date = date,
+            Message::RateCodeChanged(index, code) => {
+                if let Some(row) = self.rates.rows.get_mut(index) {
+                    row.0 = code
+                }
+            }
+            Message::RateValueChanged(index, rate) => {
+                if let Some(row) = self.rates.rows.get_mut(index) {
+                    row.1 = rate
+                }
+            }
+            Message::RateAdded => self.rates.rows.push(Default::default()),
+            Message::RateRemoved(index) => {
+                if index < self.rates.rows.len() {
+                    self.rates.rows.remove(index);
                 }
             }
-            Message::Reciprocal => {
-                if let Ok(n) = self.input.parse::<f64>() {
-                    if n != 0.0 {
-                        self.result = (1.0 / n).to_string();
-                    } else {
-                        self.result = "Error".into();
+            Message::RatesMaxAgeChanged(days) => self.rates_max_age = days,
+            Message::RatesPathChanged(path) => self.rates_path = path,
+            Message::RatesImported => {
+                match currency::Table::load(std::path::Path::new(self.rates_path.trim())) {
+                    Ok(table) => {
+                        self.rates = currency::Editor::new(&table, self.locale);
+                        self.save_rates();
                     }
-                    self.input.clear();
+                    Err(error) => self.rates_status = format!("Error: {error}"),
                 }
             }
-            Message::RootY => {
-                if let (Some(base), Ok(root)) = (self.operand, self.input.parse::<f64>()) {
-                    self.result = base.powf(1.0 / root).to_string();
-                    self.input.clear();
-                    self.operand = None;
+            Message::RatesSaved => self.save_rates(),
+            Message::RegisterPressed(register) => self.press_register(register),
+            Message::ToggleTiming => {
+                self.tvm.timing = match self.tvm.timing {
+                    finance::Timing::Begin => finance::Timing::End,
+                    finance::Timing::End => finance::Timing::Begin,
+                };
+                self.schedule.clear();
+            }
+            Message::ClearRegisters => {
+                self.tvm = finance::Tvm::default();
+                self.schedule.clear();
+            }
+            Message::Amortize => match self.tvm.schedule() {
+                Ok(schedule) => self.schedule = schedule,
+                Err(error) => self.result = format!("Error: {error}"),
+            },
+            Message::SchedulePathChanged(path) => self.schedule_path = path,
+            Message::ScheduleExported => {
+                let path = std::path::Path::new(self.schedule_path.trim());
+                self.schedule_status = match finance::export_schedule(&self.schedule, path) {
+                    Ok(()) => format!(
+                        "Exported {} periods to {}",
+                        self.schedule.len(),
+                        path.display()
+                    ),
+                    Err(error) => format!("Error: {error}"),
+                };
+            }
+            Message::PropagationSelected(propagation) => self.propagation = propagation,
+            Message::DegreeChanged(degree) => self.polynomial.set_degree(degree),
+            Message::CoefficientChanged(power, value) => {
+                if let Some(coefficient) = self.polynomial.coefficients.get_mut(power) {
+                    *coefficient = value
                 }
             }
-            Message::Exponential => {
-                if let Ok(n) = self.input.parse::<f64>() {
-                    self.result = n.exp().to_string();
-                    self.input.clear();
+            Message::FindRoots => self.find_roots(),
+            Message::WorksheetEdited(action) => self.edit_worksheet(action),
+            Message::WorksheetPathChanged(path) => self.worksheet_path = path,
+            Message::WorksheetSaved => {
+                let path = worksheet::with_extension(&self.worksheet_path);
+                self.worksheet_status = match worksheet::save(&path, &self.worksheet.text()) {
+                    Ok(()) => format!("Saved to {}", path.display()),
+                    Err(error) => format!("Error: {error}"),
+                };
+            }
+            Message::WorksheetOpened => {
+                let path = worksheet::with_extension(&self.worksheet_path);
+                match worksheet::load(&path) {
+                    Ok(sheet) => {
+                        self.worksheet = text_editor::Content::with_text(&sheet);
+                        self.worksheet_status = format!("Opened {}", path.display());
+                        self.refresh_worksheet();
+                    }
+                    Err(error) => self.worksheet_status = format!("Error: {error}"),
                 }
             }
-            Message::Euler => {
-                self.result = E.to_string();
+            Message::FormatsToggled(show) => self.show_formats = show,
+            Message::ApplyFunction(function) => self.apply_function(&function),
+            Message::WindowResized(size) => self.sizing = sizing::Sizing::new(size),
+            Message::ToggleDrawer => self.drawer_open = !self.drawer_open,
+            Message::HistoryToggled(show) => self.show_history = show,
+            Message::LocaleSelected(locale) => {
+                let previous = std::mem::replace(&mut self.locale, locale);
+                // Numbers already in the editors are rewritten to the new separators.
+                let cells = self
+                    .matrices
+                    .iter_mut()
+                    .flat_map(|editor| editor.cells.iter_mut().flatten())
+                    .chain(&mut self.polynomial.coefficients)
+                    .chain(self.rates.rows.iter_mut().map(|(_, rate)| rate));
+                for cell in cells {
+                    *cell = previous.relocalize(cell, locale);
+                }
+                self.refresh_worksheet();
             }
-            Message::EE => {
-                self.input.push_str("e");
+            Message::ConstantsToggled(show) => self.show_constants = show,
+            Message::ConstantSearchChanged(search) => self.constant_search = search,
+            Message::SignificantFiguresToggled(on) => self.significant_figures = on,
+            Message::ScriptPathChanged(path) => self.script_path = path,
+            Message::ToggleRecording => match self.recording.take() {
+                None => {
+                    self.recording = Some(Vec::new());
+                    self.script_status = "Recording…".into();
+                }
+                Some(messages) => {
+                    let path = script::with_extension(&self.script_path);
+                    self.script_status = match script::save(&path, &messages) {
+                        Ok(()) => {
+                            format!("Saved {} messages to {}", messages.len(), path.display())
+                        }
+                        Err(error) => format!("Error: {error}"),
+                    };
+                }
+            },
+            Message::ReplayScript => {
+                let path = script::with_extension(&self.script_path);
+                match script::load(&path) {
+                    Ok(steps) => {
+                        let (report, task) = script::replay(self, steps);
+                        self.script_status = report.to_string();
+                        return task;
+                    }
+                    Err(error) => self.script_status = format!("Error: {error}"),
+                }
             }
-            Message::Percentage => {
-                if let Ok(n) = self.input.parse::<f64>() {
-                    self.result = (n / 100.0).to_string();
+            Message::CopyToClipboard(text) => return iced::clipboard::write(text),
+            Message::Clear => self.clear(),
+            Message::Calculate => match self.mode {
+                Mode::Standard
+                | Mode::Matrix
+                | Mode::Units
+                | Mode::Currency
+                | Mode::Finance
+                | Mode::Uncertainty
+                | Mode::Polynomial => self.calculate(),
+                Mode::Worksheet => {
+                    self.edit_worksheet(text_editor::Action::Edit(text_editor::Edit::Enter))
+                }
+                Mode::Solve => self.solve(),
+            },
+            Message::OperatorPressed(op) => self.handle_operator(op),
+            Message::TrigFunctionPressed(trig_fn) => self.apply_trig_function(trig_fn),
+            Message::LogFunctionPressed(log_fn) => self.apply_log_function(log_fn),
+            Message::Exponentiate => self.prepare_exponentiation(),
+            Message::SquareRoot => self.apply_function("sqrt(x)"),
+            Message::ToggleAngleMode => {
+                self.toggle_angle_mode();
+                self.refresh_worksheet();
+            }
+            // Keys go through the evaluator so that uncertainties and units carry over.
+            Message::Factorial => self.apply_function("x!"),
+            Message::Square => self.apply_function("x^2"),
+            Message::Cube => self.apply_function("x^3"),
+            Message::Reciprocal => match self.input_value() {
+                Some(expr::Value::Number(0.0)) => {
+                    self.result = "Error".into();
                     self.input.clear();
                 }
+                _ => self.apply_function("1/x"),
+            },
+            Message::RootY => {
+                if let (Some(base), Some(degree)) = (self.operand.clone(), self.input_value()) {
+                    let context = self.context();
+                    let root = expr::binary(Operator::Divide, 1.0.into(), degree, &context)
+                        .and_then(|power| {
+                            expr::binary(Operator::Exponentiate, base, power, &context)
+                        });
+                    self.result = match root {
+                        Ok(root) => self.shown(&root, self.operand_figures),
+                        Err(error) => format!("Error: {error}"),
+                    };
+                    self.input.clear();
+                    self.operand = None;
+                }
+            }
+            Message::Exponential => self.apply_function("exp(x)"),
+            Message::Euler => {
+                self.result = E.to_string();
             }
+            Message::EE => self.start_exponent(),
+            Message::Negate => self.negate_input(),
+            Message::Percentage => self.apply_function("x/100"),
         }
         Task::none()
     }
@@ -157,29 +669,365 @@ impl Calculator {
         self.result.clear();
         self.operand = None;
         self.operator = None;
+        self.operand_figures = None;
+    }
+
+    /// Starts the exponent of the number being typed; without one, the mantissa is 1.
+    fn start_exponent(&mut self) {
+        let ends_in_mantissa = self
+            .input
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_ascii_digit() || c == self.locale.decimal());
+        if !ends_in_mantissa {
+            self.input.push('1');
+        }
+        self.input.push('e');
+        self.entering_exponent = true;
+    }
+
+    /// The exponent being typed after the mantissa's `e`, if the input ends in one.
+    fn exponent(&self) -> Option<&str> {
+        let (mantissa, exponent) = self.input.rsplit_once('e')?;
+        let digits = exponent.strip_prefix('-').unwrap_or(exponent);
+        let valid = mantissa.ends_with(|c: char| c.is_ascii_digit() || c == self.locale.decimal())
+            && digits.chars().all(|c| c.is_ascii_digit());
+        valid.then_some(exponent)
+    }
+
+    /// Handles a key while the exponent is being typed; returns whether it was used.
+    ///
+    /// Digits extend the exponent, and EE, or a minus before any digits, changes its
+    /// sign. Anything else ends the entry, dropping an exponent without digits.
+    fn enter_exponent(&mut self, message: &Message) -> bool {
+        let Some(exponent) = self.exponent() else {
+            self.entering_exponent = false;
+            return false;
+        };
+        let negative = exponent.starts_with('-');
+        let empty = exponent.trim_start_matches('-').is_empty();
+        match message {
+            Message::Input(digits) if digits.chars().all(|c| c.is_ascii_digit()) => false,
+            Message::EE | Message::Negate => {
+                self.toggle_exponent_sign(negative);
+                true
+            }
+            Message::OperatorPressed(Operator::Subtract) if empty => {
+                self.toggle_exponent_sign(negative);
+                true
+            }
+            Message::InputChanged(_) => false,
+            _ => {
+                if empty {
+                    let mantissa = self.input.len() - exponent.len() - 1;
+                    self.input.truncate(mantissa);
+                }
+                self.entering_exponent = false;
+                false
+            }
+        }
+    }
+
+    fn toggle_exponent_sign(&mut self, negative: bool) {
+        let Some(e) = self.input.rfind('e') else {
+            return;
+        };
+        if negative {
+            self.input.remove(e + 1);
+        } else {
+            self.input.insert(e + 1, '-');
+        }
+    }
+
+    /// Whether the input is at the start of an expression or right after an operator.
+    fn expects_operand(&self) -> bool {
+        let input = self.input.trim_end();
+        match input.chars().last() {
+            None => self.operator.is_some() || self.operand.is_none(),
+            Some(last) => OPERAND_FOLLOWS.contains(last),
+        }
+    }
+
+    /// Changes the sign of the number at the end of the input, or of the next one typed.
+    fn negate_input(&mut self) {
+        let mut chars: Vec<char> = self.input.chars().collect();
+        let is_digit = |c: char| c.is_ascii_digit() || c == self.locale.decimal();
+        let mut start = chars.len();
+        while start > 0 && is_digit(chars[start - 1]) {
+            start -= 1;
+        }
+        // Step over an exponent, as in `1.5e-3`, to the mantissa.
+        let exponent = match chars[..start] {
+            [.., c, 'e', '-' | '−'] if is_digit(c) => 2,
+            [.., c, 'e'] if is_digit(c) => 1,
+            _ => 0,
+        };
+        if exponent > 0 && start < chars.len() {
+            start -= exponent;
+            while start > 0 && is_digit(chars[start - 1]) {
+                start -= 1;
+            }
+        }
+
+        let sign = start
+            .checked_sub(1)
+            .filter(|&i| matches!(chars[i], '-' | '−'));
+        let unary = sign.is_some_and(|i| {
+            chars[..i]
+                .iter()
+                .rev()
+                .find(|c| !c.is_whitespace())
+                .is_none_or(|&c| OPERAND_FOLLOWS.contains(c))
+        });
+        match sign {
+            Some(i) if unary => {
+                chars.remove(i);
+            }
+            _ => chars.insert(start, '-'),
+        }
+        self.input = chars.into_iter().collect();
+    }
+
+    /// Significant figures of the numbers typed into the input.
+    fn input_figures(&self) -> Option<u32> {
+        significant::least(&self.delocalize(&self.input).ok()?)
+    }
+
+    /// Writes a result, rounded to `figures` significant figures if that is turned on.
+    fn rounded(&self, value: f64, figures: Option<u32>) -> String {
+        match figures.filter(|_| self.significant_figures) {
+            Some(figures) => significant::round(value, figures),
+            None => value.to_string(),
+        }
+    }
+
+    /// Writes a value as a result, rounding plain numbers like [`Self::rounded`].
+    fn shown(&self, value: &expr::Value, figures: Option<u32>) -> String {
+        match value {
+            expr::Value::Number(n) => self.rounded(*n, figures),
+            value => value.to_string(),
+        }
     }
 
     fn calculate(&mut self) {
-        if let (Some(operator), Some(operand)) = (self.operator, self.operand) {
-            if let Ok(current_value) = self.input.parse::<f64>() {
-                let result = match operator {
-                    Operator::Add => operand + current_value,
-                    Operator::Subtract => operand - current_value,
-                    Operator::Multiply => operand * current_value,
-                    Operator::Divide => operand / current_value,
-                    Operator::Exponentiate => operand.powf(current_value),
+        if let (Some(operator), Some(operand)) = (self.operator, self.operand.clone()) {
+            if let Some(current_value) = self.input_value() {
+                if operator == Operator::Divide && current_value == expr::Value::Number(0.0) {
+                    self.result = "Error".into();
+                    self.input.clear();
+                    self.operand = None;
+                    self.operator = None;
+                    return;
+                }
+                let figures = match (self.operand_figures, self.input_figures()) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
                 };
-                self.result = result.to_string();
+                match expr::binary(operator, operand, current_value, &self.context()) {
+                    Ok(result) => {
+                        self.result = self.shown(&result, figures);
+                        self.operand_figures = figures;
+                        self.input.clear();
+                        self.operand = Some(result);
+                        self.operator = None;
+                    }
+                    Err(error) => self.result = format!("Error: {error}"),
+                }
+            }
+        } else if !self.input.trim().is_empty() {
+            let context = self.context();
+            match self.evaluate_input(&context) {
+                Ok(result) => {
+                    let figures = self.input_figures();
+                    let shown = self.shown(&result, figures);
+                    self.result = shown.clone();
+                    for note in context.take_notes() {
+                        self.result.push_str(&format!("\n{note}"));
+                    }
+                    self.record(shown);
+                    self.operand_figures = figures;
+                    self.input.clear();
+                    self.operand = Some(result);
+                }
+                Err(error) => self.result = format!("Error: {error}"),
+            }
+        }
+    }
+
+    /// Adds the input and its result to the history, dropping the oldest entry when full.
+    fn record(&mut self, result: String) {
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+        self.history.push((self.input.clone(), result));
+    }
+
+    fn solve(&mut self) {
+        let context = self.context();
+        let root = self
+            .delocalize(&self.solve_start)
+            .map_err(solver::Error::from)
+            .and_then(|start| {
+                let start = solver::Start::parse(&start, &context)?;
+                let input = self.delocalize(&self.input)?;
+                solver::Equation::parse(&input, context)?.solve(start)
+            });
+        self.result = match root {
+            Ok(root) => root.to_string(),
+            Err(error) => format!("Error: {error}"),
+        };
+    }
+
+    /// Stores the input in `register`, or computes the register when the input is empty.
+    fn press_register(&mut self, register: finance::Register) {
+        let value = if self.input.trim().is_empty() {
+            self.tvm.solve(register).map_err(expr::Error::from)
+        } else {
+            self.evaluate_input(&self.context())
+                .and_then(|value| value.number())
+        };
+        match value {
+            Ok(value) => {
+                self.tvm.set(register, value);
+                self.result = format!("{register} = {value}");
                 self.input.clear();
-                self.operand = Some(result);
-                self.operator = None;
+                self.schedule.clear();
+            }
+            Err(error) => self.result = format!("Error: {error}"),
+        }
+    }
+
+    fn edit_worksheet(&mut self, action: text_editor::Action) {
+        let edited = action.is_edit();
+        self.worksheet.perform(action);
+        if edited {
+            self.refresh_worksheet();
+        }
+    }
+
+    /// Re-evaluates the sheet; done on edits rather than in `view` so `rand()` stays put.
+    fn refresh_worksheet(&mut self) {
+        if self.mode == Mode::Worksheet {
+            self.worksheet_results =
+                worksheet::evaluate(&self.worksheet.text(), &self.context(), self.locale);
+        }
+    }
+
+    fn find_roots(&mut self) {
+        let coefficients = match self.polynomial.coefficients(self.locale) {
+            Ok(coefficients) => coefficients,
+            Err((power, cell)) => {
+                self.result = format!("Error: '{cell}' is not a number (x^{power})");
+                return;
+            }
+        };
+        match polynomial::roots(&coefficients) {
+            Ok(roots) => {
+                let real = roots.iter().filter(|root| root.is_real()).count();
+                self.result = format!(
+                    "{} roots: {real} real, {} complex",
+                    roots.len(),
+                    roots.len() - real
+                );
+                self.roots = roots;
+            }
+            Err(error) => {
+                self.result = format!("Error: {error}");
+                self.roots.clear();
+            }
+        }
+    }
+
+    fn save_rates(&mut self) {
+        let saved = self.rates.table(self.locale).and_then(|table| {
+            let path = self
+                .rates_file
+                .clone()
+                .ok_or_else(|| currency::Error::Io("nowhere to save the rates".into()))?;
+            table.save(&path)?;
+            Ok(path)
+        });
+        self.rates_status = match saved {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(error) => format!("Error: {error}"),
+        };
+    }
+
+    fn rates_max_age(&self) -> i64 {
+        self.rates_max_age
+            .trim()
+            .parse()
+            .unwrap_or(currency::DEFAULT_MAX_AGE_DAYS)
+    }
+
+    fn delocalize(&self, text: &str) -> Result<String, expr::Error> {
+        Ok(self.locale.delocalize(text)?)
+    }
+
+    fn evaluate_input(&self, context: &expr::Context) -> Result<expr::Value, expr::Error> {
+        expr::evaluate(&self.delocalize(&self.input)?, context)
+    }
+
+    /// The input evaluated with the locale's separators, keeping any uncertainty or
+    /// unit; `None` when it does not evaluate.
+    fn input_value(&self) -> Option<expr::Value> {
+        self.evaluate_input(&self.context()).ok()
+    }
+
+    /// The input typeset as math, or both sides of it in solve mode.
+    fn formula(&self) -> Option<typeset::Formula> {
+        let input = self.delocalize(&self.input).ok()?;
+        let sides = input
+            .split('=')
+            .map(expr::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        // A lone number would only repeat the input.
+        if sides
+            .iter()
+            .all(|side| matches!(side, expr::Expr::Number(_) | expr::Expr::Integer(_)))
+        {
+            return None;
+        }
+        Some(typeset::Formula::new(
+            sides,
+            self.locale,
+            self.sizing.text_size,
+        ))
+    }
+
+    /// The result as a plain number, if it is one.
+    fn result_value(&self) -> Option<f64> {
+        self.result.lines().next()?.trim().parse().ok()
+    }
+
+    fn context(&self) -> expr::Context {
+        let mut context = expr::Context::new(self.angle_mode);
+        context.propagation = self.propagation;
+        if let Ok(table) = self.rates.table(self.locale) {
+            let status = table.status(chrono::Local::now().date_naive(), self.rates_max_age());
+            context.set_currencies(table, status);
+        }
+        // Only in matrix mode, so that A, B and C mean ampere, byte and coulomb elsewhere.
+        if self.mode == Mode::Matrix {
+            for (name, editor) in MATRIX_NAMES.iter().zip(&self.matrices) {
+                if let Ok(matrix) = editor.matrix(self.locale) {
+                    context.set(name, matrix);
+                }
             }
         }
+        context
     }
 
     fn handle_operator(&mut self, op: Operator) {
+        // A minus where a number should come is the sign of that number.
+        if op == Operator::Subtract && self.expects_operand() {
+            self.input.push('-');
+            return;
+        }
         if self.operand.is_none() {
-            self.operand = self.input.parse::<f64>().ok();
+            self.operand = self.input_value();
+            self.operand_figures = self.input_figures();
         } else {
             self.calculate();
         }
@@ -188,48 +1036,34 @@ impl Calculator {
     }
 
     fn apply_trig_function(&mut self, trig_fn: TrigFunction) {
-        if let Ok(angle) = self.input.parse::<f64>() {
-            let angle_radians = if self.angle_mode == AngleMode::Degrees {
-                angle.to_radians()
-            } else {
-                angle
-            };
-            let result = match trig_fn {
-                TrigFunction::Sine => angle_radians.sin(),
-                TrigFunction::Cosine => angle_radians.cos(),
-                TrigFunction::Tangent => angle_radians.tan(),
-            };
-            self.result = result.to_string();
-            self.input.clear();
-        }
+        self.apply_function(&format!("{}({})", trig_fn.name(), keypad::ARGUMENT));
     }
 
     fn apply_log_function(&mut self, log_fn: LogFunction) {
-        if let Ok(value) = self.input.parse::<f64>() {
-            let result = match log_fn {
-                LogFunction::Log10 => value.log10(),
-                LogFunction::Ln => value.ln(),
+        self.apply_function(&format!("{}({})", log_fn.name(), keypad::ARGUMENT));
+    }
+
+    /// Evaluates `function` with the input's value as [`keypad::ARGUMENT`].
+    fn apply_function(&mut self, function: &str) {
+        if let Some(x) = self.input_value() {
+            let mut context = self.context();
+            context.set(keypad::ARGUMENT, x);
+            self.result = match expr::evaluate(function, &context) {
+                Ok(value) => self.shown(&value, self.input_figures()),
+                Err(error) => format!("Error: {error}"),
             };
-            self.result = result.to_string();
             self.input.clear();
         }
     }
 
     fn prepare_exponentiation(&mut self) {
-        if let Ok(base) = self.input.parse::<f64>() {
+        if let Some(base) = self.input_value() {
             self.operand = Some(base);
             self.operator = Some(Operator::Exponentiate);
             self.input.clear();
         }
     }
 
-    fn apply_root(&mut self, degree: f64) {
-        if let Ok(value) = self.input.parse::<f64>() {
-            self.result = value.powf(1.0 / degree).to_string();
-            self.input.clear();
-        }
-    }
-
     fn toggle_angle_mode(&mut self) {
         self.angle_mode = match self.angle_mode {
             AngleMode::Degrees => AngleMode::Radians,
@@ -237,118 +1071,695 @@ impl Calculator {
         };
     }
 
-    fn factorial(n: u64) -> u64 {
-        (1..=n).product()
+    fn calc_button<'a>(&self, label: &'a str, message: Message) -> Button<'a, Message> {
+        let sizing = self.sizing;
+        button(
+            text(label)
+                .size(sizing.text_size)
+                .align_x(Center)
+                .align_y(Center),
+        )
+        .width(sizing.key_width)
+        .height(sizing.key_height)
+        .on_press(message)
     }
 
-    fn scientific_buttons(&self) -> Column<Message> {
+    /// Lays the keys out in rows, wrapping any that are wider than the window allows.
+    fn keypad<'a>(&'a self, rows: &'a [Vec<keypad::Key>]) -> Column<'a, Message> {
+        let mut keypad = Column::new();
+        for keys in rows {
+            let mut row = Row::new();
+            let mut cells = 0;
+            for key in keys {
+                if cells > 0 && cells + key.width > self.sizing.columns {
+                    keypad = keypad.push(row);
+                    row = Row::new();
+                    cells = 0;
+                }
+                cells += key.width;
+                row = row.push(self.key(key));
+            }
+            keypad = keypad.push(row);
+        }
+        keypad
+    }
+
+    fn key<'a>(&'a self, key: &'a keypad::Key) -> Button<'a, Message> {
+        let label = match (&key.binding, key.label.as_str()) {
+            (keypad::Binding::Action(keypad::Action::AngleMode), "") => match self.angle_mode {
+                AngleMode::Degrees => "Deg",
+                AngleMode::Radians => "Rad",
+            },
+            (_, ".") => self.locale.decimal_label(),
+            (_, ",") => self.locale.separator_label(),
+            (_, label) => label,
+        };
+        self.calc_button(label, key.message())
+            .width(self.sizing.keys(key.width as f32))
+    }
+
+    fn solve_panel(&self) -> Row<'_, Message> {
+        row![
+            text_input("guess x0, or bracket a, b", &self.solve_start)
+                .on_input(Message::SolveStartChanged)
+                .on_submit(Message::Calculate)
+                .size(self.sizing.text_size)
+                .width(Fill),
+            self.calc_button("d/dx", Message::Input("d/dx ".into())),
+            self.calc_button("∫", Message::Input("∫ ".into())),
+            self.calc_button("x", Message::Input(solver::UNKNOWN.into())),
+            self.calc_button("=", Message::Input("=".into())),
+            self.calc_button(self.locale.separator_label(), Message::Input(",".into()))
+        ]
+        .spacing(2)
+    }
+
+    fn matrix_panel(&self) -> Column<'_, Message> {
+        let editor = &self.matrices[self.selected_matrix];
+
+        let mut selector = row![].spacing(2);
+        for (index, name) in MATRIX_NAMES.iter().enumerate() {
+            let style = if index == self.selected_matrix {
+                button::primary
+            } else {
+                button::secondary
+            };
+            selector = selector.push(
+                self.calc_button(name, Message::MatrixSelected(index))
+                    .style(style),
+            );
+        }
+        let (rows, cols) = (editor.rows, editor.cols);
+        selector = selector
+            .push(
+                text(format!("{rows}×{cols}"))
+                    .size(self.sizing.text_size)
+                    .width(self.sizing.key_width)
+                    .height(self.sizing.key_height)
+                    .align_x(Center)
+                    .align_y(Center),
+            )
+            .push(self.calc_button("−r", Message::MatrixResized(rows - 1, cols)))
+            .push(self.calc_button("+r", Message::MatrixResized(rows + 1, cols)))
+            .push(self.calc_button("−c", Message::MatrixResized(rows, cols - 1)))
+            .push(self.calc_button("+c", Message::MatrixResized(rows, cols + 1)));
+
+        let mut grid = column![].spacing(2);
+        for i in 0..rows {
+            let mut cells = row![].spacing(2);
+            for j in 0..cols {
+                cells = cells.push(
+                    text_input("0", &editor.cells[i][j])
+                        .on_input(move |value| Message::MatrixCellChanged(i, j, value))
+                        .size(20)
+                        .width(self.sizing.share(matrix::MAX_SIZE)),
+                );
+            }
+            grid = grid.push(cells);
+        }
+
+        let status = match editor.matrix(self.locale) {
+            Ok(_) => String::new(),
+            Err((row, col, cell)) => format!("Error: '{cell}' in row {row}, column {col}"),
+        };
+
+        let insert = |label: &'static str, snippet: &str| {
+            self.calc_button(label, Message::Input(snippet.into()))
+        };
         column![
+            selector,
+            grid,
+            text(status),
             row![
-                button(
-                    text(match self.angle_mode {
-                        AngleMode::Degrees => "Deg",
-                        AngleMode::Radians => "Rad",
-                    })
-                    .size(24)
-                    .align_x(Center)
-                    .align_y(Center)
+                insert("A", "A"),
+                insert("B", "B"),
+                insert("C", "C"),
+                insert(self.locale.separator_label(), ","),
+                insert("det", "det("),
+                insert("inv", "inv("),
+                insert("Aᵀ", "trans("),
+                insert("rank", "rank(")
+            ]
+            .wrap(),
+            row![
+                insert("solve", "solve("),
+                insert("dot", "dot("),
+                insert("cross", "cross("),
+                insert("x⁻¹", "^-1")
+            ]
+        ]
+        .spacing(2)
+    }
+
+    fn conversion_panel(&self) -> Column<'_, Message> {
+        let units = self.conversion_category.units();
+        let conversion = match self.evaluate_input(&self.context()) {
+            Ok(expr::Value::Number(amount)) => {
+                match units::convert(amount, &self.conversion_from, &self.conversion_to) {
+                    Ok(result) => format!("{amount} {} = {result}", self.conversion_from),
+                    Err(error) => format!("Error: {error}"),
+                }
+            }
+            Ok(_) => "Enter a plain number to convert".into(),
+            Err(_) => String::new(),
+        };
+
+        column![
+            row![
+                pick_list(
+                    units::Category::ALL,
+                    Some(self.conversion_category),
+                    Message::ConversionCategorySelected
                 )
-                .width(150)
-                .height(50)
-                .on_press(Message::ToggleAngleMode),
-                calc_button("(", Message::Input("(".into())),
-                calc_button(")", Message::Input(")".into()))
-            ],
+                .text_size(20)
+                .width(self.sizing.keys(2.0)),
+                pick_list(
+                    units.clone(),
+                    Some(self.conversion_from.clone()),
+                    Message::ConversionFromSelected
+                )
+                .text_size(20)
+                .width(Fill),
+                self.calc_button("⇄", Message::SwapConversionUnits),
+                pick_list(
+                    units,
+                    Some(self.conversion_to.clone()),
+                    Message::ConversionToSelected
+                )
+                .text_size(20)
+                .width(Fill),
+            ]
+            .spacing(2)
+            .align_y(Center)
+            .width(Fill),
+            text(self.locale.display(&conversion)).size(20),
             row![
-                calc_button("sin", Message::TrigFunctionPressed(TrigFunction::Sine)),
-                calc_button("cos", Message::TrigFunctionPressed(TrigFunction::Cosine)),
-                calc_button("tan", Message::TrigFunctionPressed(TrigFunction::Tangent)),
-                calc_button("π", Message::Input(PI.to_string()))
-            ],
+                self.calc_button("to", Message::Input(" to ".into())),
+                self.calc_button("km", Message::Input(" km".into())),
+                self.calc_button("h", Message::Input(" h".into())),
+                self.calc_button("kg", Message::Input(" kg".into())),
+                self.calc_button("°C", Message::Input(" °C".into())),
+                self.calc_button("°F", Message::Input(" °F".into())),
+                self.calc_button("MB", Message::Input(" MB".into())),
+                self.calc_button("mi", Message::Input(" mi".into()))
+            ]
+            .wrap()
+        ]
+        .spacing(2)
+    }
+
+    fn currency_panel(&self) -> Column<'_, Message> {
+        let status = match self.rates.table(self.locale) {
+            Ok(table) => table.status(chrono::Local::now().date_naive(), self.rates_max_age()),
+            Err(error) => format!("Error: {error}"),
+        };
+
+        let mut rates = column![].spacing(2);
+        for (index, (code, rate)) in self.rates.rows.iter().enumerate() {
+            rates = rates.push(
+                row![
+                    text_input("USD", code)
+                        .on_input(move |code| Message::RateCodeChanged(index, code))
+                        .size(20)
+                        .width(self.sizing.keys(2.0)),
+                    text_input("rate per base unit", rate)
+                        .on_input(move |rate| Message::RateValueChanged(index, rate))
+                        .size(20)
+                        .width(Fill),
+                    button(text("✕").size(20).align_x(Center))
+                        .width(self.sizing.key_width)
+                        .on_press(Message::RateRemoved(index)),
+                ]
+                .spacing(2),
+            );
+        }
+
+        column![
+            text(status).size(20),
             row![
-                calc_button("x!", Message::Factorial),
-                calc_button("x²", Message::Square),
-                calc_button("x³", Message::Cube),
-                calc_button("xy", Message::Exponentiate)
-            ],
+                text("Base").size(20),
+                text_input("EUR", &self.rates.base)
+                    .on_input(Message::RateBaseChanged)
+                    .size(20)
+                    .width(self.sizing.key_width),
+                text("Date").size(20),
+                text_input("YYYY-MM-DD", &self.rates.date)
+                    .on_input(Message::RateDateChanged)
+                    .size(20)
+                    .width(self.sizing.keys(2.0)),
+                text("Stale after").size(20),
+                text_input("days", &self.rates_max_age)
+                    .on_input(Message::RatesMaxAgeChanged)
+                    .size(20)
+                    .width(Fill),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            scrollable(rates).height(150),
             row![
-                calc_button("1/x", Message::Reciprocal),
-                calc_button("√x", Message::SquareRoot),
-                calc_button("x√y", Message::RootY),
-                calc_button("EE", Message::EE)
-            ],
+                button(text("Add rate").size(20)).on_press(Message::RateAdded),
+                button(text("Save").size(20)).on_press(Message::RatesSaved),
+                text_input("path to .json or .csv", &self.rates_path)
+                    .on_input(Message::RatesPathChanged)
+                    .on_submit(Message::RatesImported)
+                    .size(20)
+                    .width(Fill),
+                button(text("Import").size(20)).on_press(Message::RatesImported),
+            ]
+            .spacing(2),
+            text(&self.rates_status),
+        ]
+        .spacing(4)
+        .width(Fill)
+    }
+
+    fn finance_panel(&self) -> Column<'_, Message> {
+        let mut registers = row![].spacing(2);
+        let mut keys = row![];
+        for register in finance::Register::ALL {
+            let value = match register {
+                finance::Register::N | finance::Register::Rate => {
+                    format!("{:.4}", self.tvm.get(register))
+                }
+                _ => format!("{:.2}", self.tvm.get(register)),
+            };
+            registers = registers.push(
+                column![text(register.label()).size(16), text(value).size(20)]
+                    .width(self.sizing.share(finance::Register::ALL.len() + 1)),
+            );
+            keys =
+                keys.push(self.calc_button(register.label(), Message::RegisterPressed(register)));
+        }
+        registers = registers.push(text(self.tvm.timing.to_string()).size(20));
+
+        let small_button =
+            |label: &'static str, message| button(text(label).size(20)).on_press(message);
+        let mut panel = column![
+            registers,
+            keys.push(self.calc_button("BEG", Message::ToggleTiming).style(
+                if self.tvm.timing == finance::Timing::Begin {
+                    button::primary
+                } else {
+                    button::secondary
+                }
+            ))
+            .push(self.calc_button("npv", Message::Input("npv(".into())))
+            .push(self.calc_button("irr", Message::Input("irr(".into())))
+            .wrap(),
             row![
-                calc_button("log", Message::LogFunctionPressed(LogFunction::Log10)),
-                calc_button("ln", Message::LogFunctionPressed(LogFunction::Ln)),
-                calc_button("eˣ", Message::Exponential),
-                calc_button("e", Message::Euler)
+                small_button("Amortize", Message::Amortize),
+                small_button("Clear registers", Message::ClearRegisters),
+                self.calc_button(self.locale.separator_label(), Message::Input(",".into())),
             ]
+            .spacing(2)
+            .align_y(Center),
         ]
+        .spacing(2);
+
+        if !self.schedule.is_empty() {
+            let line =
+                |period: &str, payment: &str, interest: &str, principal: &str, balance: &str| {
+                    text(format!(
+                        "{period:>4} {payment:>12} {interest:>12} {principal:>12} {balance:>14}"
+                    ))
+                    .size(16)
+                };
+            let mut rows = column![];
+            for period in &self.schedule {
+                rows = rows.push(line(
+                    &period.number.to_string(),
+                    &format!("{:.2}", period.payment),
+                    &format!("{:.2}", period.interest),
+                    &format!("{:.2}", period.principal),
+                    &format!("{:.2}", period.balance),
+                ));
+            }
+            panel = panel
+                .push(line("#", "payment", "interest", "principal", "balance"))
+                .push(scrollable(rows).height(150))
+                .push(
+                    row![
+                        text_input("path to .csv", &self.schedule_path)
+                            .on_input(Message::SchedulePathChanged)
+                            .on_submit(Message::ScheduleExported)
+                            .size(20)
+                            .width(Fill),
+                        small_button("Export CSV", Message::ScheduleExported),
+                    ]
+                    .spacing(2),
+                )
+                .push(text(&self.schedule_status));
+        }
+
+        panel.width(Fill)
     }
 
-    fn basic_buttons(&self) -> Column<Message> {
+    fn uncertainty_panel(&self) -> Row<'_, Message> {
+        row![
+            pick_list(
+                uncertainty::Propagation::ALL,
+                Some(self.propagation),
+                Message::PropagationSelected
+            )
+            .text_size(20)
+            .width(Fill),
+            self.calc_button("±", Message::Input(" ± ".into())),
+            self.calc_button("(", Message::Input("(".into())),
+            self.calc_button(")", Message::Input(")".into()))
+        ]
+        .spacing(2)
+        .align_y(Center)
+        .width(Fill)
+    }
+
+    fn polynomial_panel(&self) -> Column<'_, Message> {
+        let degree = self.polynomial.degree;
+
+        let mut cells = Vec::new();
+        for power in (0..=degree).rev() {
+            let label = match power {
+                0 => String::new(),
+                1 => "x".into(),
+                _ => format!("x^{power}"),
+            };
+            cells.push(
+                column![
+                    text(label).size(16),
+                    text_input("0", &self.polynomial.coefficients[power])
+                        .on_input(move |value| Message::CoefficientChanged(power, value))
+                        .on_submit(Message::FindRoots)
+                        .size(20)
+                        .width(self.sizing.share(6)),
+                ]
+                .into(),
+            );
+        }
+        let mut grid = column![].spacing(2);
+        let mut cells = cells.into_iter().peekable();
+        while cells.peek().is_some() {
+            grid = grid.push(Row::with_children(cells.by_ref().take(6)).spacing(2));
+        }
+
+        let mut roots = column![].spacing(2);
+        for root in &self.roots {
+            let shown = root.to_string();
+            let displayed = self.locale.display(&shown);
+            // The input holds real numbers only, so complex roots go to the clipboard.
+            let message = if root.is_real() {
+                Message::Input(shown)
+            } else {
+                Message::CopyToClipboard(displayed.clone())
+            };
+            roots = roots.push(
+                button(text(displayed).size(20))
+                    .style(button::secondary)
+                    .on_press(message),
+            );
+        }
+
         column![
             row![
-                button(text("C").size(24).align_x(Center).align_y(Center))
-                    .width(150)
-                    .height(50)
-                    .on_press(Message::Clear),
-                calc_button("%", Message::Percentage),
-                calc_button("÷", Message::OperatorPressed(Operator::Divide))
-            ],
-            row![
-                calc_button("7", Message::Input("7".into())),
-                calc_button("8", Message::Input("8".into())),
-                calc_button("9", Message::Input("9".into())),
-                calc_button("×", Message::OperatorPressed(Operator::Multiply))
-            ],
+                text(format!("Degree {degree}"))
+                    .size(self.sizing.text_size)
+                    .width(self.sizing.keys(2.0))
+                    .align_y(Center),
+                self.calc_button("−", Message::DegreeChanged(degree - 1)),
+                self.calc_button("+", Message::DegreeChanged(degree + 1)),
+                button(text("Find roots").size(20)).on_press(Message::FindRoots),
+            ]
+            .spacing(2)
+            .align_y(Center),
+            grid,
+            roots,
+        ]
+        .spacing(4)
+        .width(Fill)
+    }
+
+    fn worksheet_panel(&self) -> Column<'_, Message> {
+        column![
             row![
-                calc_button("4", Message::Input("4".into())),
-                calc_button("5", Message::Input("5".into())),
-                calc_button("6", Message::Input("6".into())),
-                calc_button("−", Message::OperatorPressed(Operator::Subtract))
-            ],
+                text_editor(&self.worksheet)
+                    .placeholder("price = 120 EUR to USD\nprice * 3\nline2 / 4")
+                    .on_action(Message::WorksheetEdited)
+                    .size(20)
+                    .height(300)
+                    .width(self.sizing.width() * 2.0 / 3.0),
+                text(self.locale.display(&self.worksheet_results.join("\n")))
+                    .size(20)
+                    .width(Fill)
+                    .align_x(Right),
+            ]
+            .spacing(4),
             row![
-                calc_button("1", Message::Input("1".into())),
-                calc_button("2", Message::Input("2".into())),
-                calc_button("3", Message::Input("3".into())),
-                calc_button("+", Message::OperatorPressed(Operator::Add))
-            ],
+                text_input("sheet.calc", &self.worksheet_path)
+                    .on_input(Message::WorksheetPathChanged)
+                    .on_submit(Message::WorksheetOpened)
+                    .size(20)
+                    .width(Fill),
+                button(text("Open").size(20)).on_press(Message::WorksheetOpened),
+                button(text("Save").size(20)).on_press(Message::WorksheetSaved),
+            ]
+            .spacing(2),
+            text(&self.worksheet_status),
+        ]
+        .spacing(4)
+        .width(Fill)
+    }
+
+    /// Recording the messages sent to a file, and replaying them.
+    fn script_panel(&self) -> Column<'_, Message> {
+        let record = if self.recording.is_some() {
+            "■ Stop"
+        } else {
+            "● Record"
+        };
+        column![
             row![
-                button(text("0").size(24).align_x(Center).align_y(Center))
-                    .width(150)
-                    .height(50)
-                    .on_press(Message::Input("0".into())),
-                calc_button(".", Message::Input(".".into())),
-                calc_button("=", Message::Calculate)
+                text_input("session.replay", &self.script_path)
+                    .on_input(Message::ScriptPathChanged)
+                    .size(14)
+                    .width(Fill),
+                button(text(record).size(14)).on_press(Message::ToggleRecording),
+                button(text("▶ Replay").size(14))
+                    .on_press_maybe(self.recording.is_none().then_some(Message::ReplayScript)),
             ]
+            .spacing(2),
+            text(&self.script_status).size(14),
         ]
+        .spacing(2)
     }
 
-    fn view(&self) -> Element<Message> {
-        let result_display = text_input("0", &self.input).size(24).width(602);
+    fn subscription(&self) -> Subscription<Message> {
+        window::resize_events().map(|(_id, size)| Message::WindowResized(size))
+    }
 
-        container(row![column![
-            text(&self.result).size(24),
-            result_display,
-            row![self.scientific_buttons(), self.basic_buttons()].spacing(2)
-        ]])
-        .width(Fill)
-        .height(Fill)
-        .align_y(Center)
-        .align_x(Center)
-        .into()
+    /// Past calculations, newest first; clicking one inserts its result.
+    fn history_panel(&self) -> Column<'_, Message> {
+        let mut entries = column![].spacing(2);
+        for (input, result) in self.history.iter().rev() {
+            entries = entries.push(
+                button(column![
+                    text(input).size(14),
+                    text(self.locale.display(result)).size(20),
+                ])
+                .style(button::text)
+                .width(Fill)
+                .on_press(Message::Input(result.clone())),
+            );
+        }
+        column![text("History").size(20), scrollable(entries).height(Fill)]
+            .spacing(4)
+            .width(sizing::SIDE_PANEL_WIDTH)
+    }
+
+    /// Searchable list of constants; clicking one inserts its name.
+    fn constants_panel(&self) -> Column<'_, Message> {
+        let constants = constants::search(&self.constant_search).map(|constant| {
+            let value = if (1e-3..1e6).contains(&constant.value.abs()) {
+                constant.value.to_string()
+            } else {
+                format!("{:e}", constant.value)
+            };
+            let value = self.locale.display(&format!("{value} {}", constant.unit));
+            button(row![
+                text(constant.symbol).size(14).width(60),
+                text(constant.description).size(14).width(Fill),
+                text(value).size(14),
+            ])
+            .style(button::text)
+            .padding([1, 4])
+            .width(Fill)
+            .on_press(Message::Input(constant.names[0].into()))
+            .into()
+        });
+        column![
+            text_input("Search constants", &self.constant_search)
+                .on_input(Message::ConstantSearchChanged),
+            scrollable(column(constants)).height(160),
+        ]
+        .spacing(4)
+    }
+
+    /// The number being typed with EE, written as `1.23 × 10⁻⁴`.
+    fn exponent_notation(&self) -> Option<String> {
+        let exponent = self.exponent().filter(|_| self.entering_exponent)?;
+        let mantissa = &self.input[..self.input.len() - exponent.len() - 1];
+        // Only the number itself, not an expression before it.
+        let start = mantissa
+            .rfind(|c: char| !(c.is_ascii_digit() || c == self.locale.decimal()))
+            .map_or(0, |i| i + 1);
+        let mantissa = &mantissa[start..];
+        let exponent = if exponent.trim_start_matches('-').is_empty() {
+            format!("{exponent}□")
+        } else {
+            exponent.to_string()
+        };
+        let mut notation = format!("{mantissa} × 10{}", significant::superscript(&exponent));
+        if self.significant_figures {
+            if let Some(figures) = significant::count(&self.locale.delocalize(mantissa).ok()?) {
+                notation.push_str(&format!("  ({figures} s.f.)"));
+            }
+        }
+        Some(notation)
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let placeholder = match self.mode {
+            Mode::Standard => "0",
+            Mode::Solve => "f(x) = g(x)",
+            Mode::Matrix => "A * B",
+            Mode::Units => "5 km / 20 min to mph",
+            Mode::Currency => "100 EUR to USD",
+            Mode::Finance => "value, then N, I/Y, PV, PMT or FV",
+            Mode::Uncertainty => "(9.81 ± 0.02) * 2",
+            Mode::Polynomial => "click a root to insert it",
+            Mode::Worksheet => "",
+        };
+        let result_display = text_input(placeholder, &self.input)
+            .on_input(Message::InputChanged)
+            .on_submit(Message::Calculate)
+            .size(self.sizing.text_size)
+            .width(Fill);
+
+        let mut settings = row![
+            pick_list(Mode::ALL, Some(self.mode), Message::ModeSelected),
+            checkbox("All formats", self.show_formats).on_toggle(Message::FormatsToggled),
+            checkbox("Constants", self.show_constants).on_toggle(Message::ConstantsToggled),
+            checkbox("Sig. figs", self.significant_figures)
+                .on_toggle(Message::SignificantFiguresToggled),
+            pick_list(
+                locale::Locale::ALL,
+                Some(self.locale),
+                Message::LocaleSelected
+            ),
+        ]
+        .spacing(10)
+        .align_y(Center);
+        if self.sizing.side_panel {
+            settings = settings
+                .push(checkbox("History", self.show_history).on_toggle(Message::HistoryToggled));
+        }
+        let mut content = column![settings.wrap()];
+        // The worksheet's own editor takes the place of the single input line.
+        if self.mode != Mode::Worksheet {
+            content =
+                content.push(text(self.locale.display(&self.result)).size(self.sizing.text_size));
+            if let Some(value) = self.result_value().filter(|_| self.show_formats) {
+                content = content.push(formats_panel(value));
+            }
+            if let Some(formula) = self.formula() {
+                let height = formula.height();
+                content = content.push(canvas(formula).width(Fill).height(height));
+            }
+            content = content.push(result_display);
+            if let Some(notation) = self.exponent_notation() {
+                content = content.push(text(notation).size(self.sizing.text_size * 0.6));
+            }
+        }
+        if self.show_constants {
+            content = content.push(self.constants_panel());
+        }
+        match self.mode {
+            Mode::Standard => {}
+            Mode::Solve => content = content.push(self.solve_panel()),
+            Mode::Matrix => content = content.push(self.matrix_panel()),
+            Mode::Units => content = content.push(self.conversion_panel()),
+            Mode::Currency => content = content.push(self.currency_panel()),
+            Mode::Finance => content = content.push(self.finance_panel()),
+            Mode::Uncertainty => content = content.push(self.uncertainty_panel()),
+            Mode::Polynomial => content = content.push(self.polynomial_panel()),
+            Mode::Worksheet => content = content.push(self.worksheet_panel()),
+        }
+
+        // Narrow windows keep the scientific and integer keys in a drawer above the basic ones.
+        if !self.sizing.drawer {
+            content = content.push(self.keypad(&self.keypad.integer)).push(
+                row![
+                    self.keypad(&self.keypad.scientific),
+                    self.keypad(&self.keypad.basic)
+                ]
+                .spacing(2),
+            );
+        } else {
+            let toggle = if self.drawer_open {
+                "▴ f(x)"
+            } else {
+                "▾ f(x)"
+            };
+            content = content.push(
+                button(text(toggle).size(self.sizing.text_size))
+                    .width(Fill)
+                    .style(button::secondary)
+                    .on_press(Message::ToggleDrawer),
+            );
+            if self.drawer_open {
+                content = content
+                    .push(self.keypad(&self.keypad.integer))
+                    .push(self.keypad(&self.keypad.scientific));
+            }
+            content = content.push(self.keypad(&self.keypad.basic));
+        }
+
+        content = content.push(self.script_panel());
+
+        let mut layout = row![content.width(self.sizing.width())].spacing(sizing::GAP);
+        if self.sizing.side_panel && self.show_history {
+            layout = layout.push(self.history_panel());
+        }
+        container(layout)
+            .width(Fill)
+            .height(Fill)
+            .align_y(Center)
+            .align_x(Center)
+            .into()
     }
 }
 
-fn calc_button(label: &str, message: Message) -> Button<Message> {
-    button(text(label).size(24).align_x(Center).align_y(Center))
-        .width(75)
-        .height(50)
-        .on_press(message)
+/// The result in every other format, each a button that copies it.
+fn formats_panel<'a>(value: f64) -> Column<'a, Message> {
+    column(
+        formats::representations(value)
+            .into_iter()
+            .map(|representation| {
+                let label = if representation.exact {
+                    representation.format.to_string()
+                } else {
+                    format!("{} ≈", representation.format)
+                };
+                button(row![
+                    text(label).size(14).width(80),
+                    text(representation.text.clone()).size(14)
+                ])
+                .style(button::text)
+                .padding([1, 4])
+                .width(Fill)
+                .on_press(Message::CopyToClipboard(representation.text))
+                .into()
+            }),
+    )
 }
 
-/*
 /// Testing the calculator
 #[cfg(test)]
 mod tests {
@@ -356,14 +1767,17 @@ mod tests {
 
     #[test]
     fn test_factorial() {
-        assert_eq!(Calculator::factorial(0), 1);
-        assert_eq!(Calculator::factorial(5), 120);
-        assert_eq!(Calculator::factorial(10), 3628800);
+        let mut calc = Calculator::with_defaults();
+        for (n, factorial) in [("0", "1"), ("5", "120"), ("10", "3628800"), ("171", "inf")] {
+            calc.input = n.to_string();
+            let _ = calc.update(Message::Factorial);
+            assert_eq!(calc.result, factorial);
+        }
     }
 
     #[test]
     fn test_apply_trig_function() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Test sine function in radians
         calc.input = "0".to_string();
@@ -385,7 +1799,7 @@ mod tests {
 
     #[test]
     fn test_apply_log_function() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Test log10
         calc.input = "100".to_string();
@@ -400,77 +1814,124 @@ mod tests {
 
     #[test]
     fn test_update_input() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Simulate user input
-        calc.update(Message::Input("5".to_string()));
+        let _ = calc.update(Message::Input("5".to_string()));
         assert_eq!(calc.input, "5");
 
-        calc.update(Message::Input("3".to_string()));
+        let _ = calc.update(Message::Input("3".to_string()));
         assert_eq!(calc.input, "53");
     }
 
     #[test]
     fn test_update_clear() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Simulate user input and then clear
-        calc.update(Message::Input("123".to_string()));
-        calc.update(Message::Clear);
+        let _ = calc.update(Message::Input("123".to_string()));
+        let _ = calc.update(Message::Clear);
         assert_eq!(calc.input, "");
         assert_eq!(calc.result, "");
         assert_eq!(calc.operand, None);
-        // assert_eq!(calc.operator, None);
     }
 
     #[test]
     fn test_update_calculate() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Simulate 5 + 3
-        calc.update(Message::Input("5".to_string()));
-        calc.update(Message::OperatorPressed(Operator::Add));
-        calc.update(Message::Input("3".to_string()));
-        calc.update(Message::Calculate);
+        let _ = calc.update(Message::Input("5".to_string()));
+        let _ = calc.update(Message::OperatorPressed(Operator::Add));
+        let _ = calc.update(Message::Input("3".to_string()));
+        let _ = calc.update(Message::Calculate);
         assert_eq!(calc.result, "8");
     }
 
     #[test]
     fn test_update_toggle_angle_mode() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Initial mode is Radians
         assert_eq!(calc.angle_mode, AngleMode::Radians);
 
         // Toggle to Degrees
-        calc.update(Message::ToggleAngleMode);
+        let _ = calc.update(Message::ToggleAngleMode);
         assert_eq!(calc.angle_mode, AngleMode::Degrees);
 
         // Toggle back to Radians
-        calc.update(Message::ToggleAngleMode);
+        let _ = calc.update(Message::ToggleAngleMode);
         assert_eq!(calc.angle_mode, AngleMode::Radians);
     }
 
     #[test]
     fn test_division_by_zero() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Simulate 1 / 0
-        calc.update(Message::Input("1".to_string()));
-        calc.update(Message::OperatorPressed(Operator::Divide));
-        calc.update(Message::Input("0".to_string()));
-        calc.update(Message::Calculate);
+        let _ = calc.update(Message::Input("1".to_string()));
+        let _ = calc.update(Message::OperatorPressed(Operator::Divide));
+        let _ = calc.update(Message::Input("0".to_string()));
+        let _ = calc.update(Message::Calculate);
         assert_eq!(calc.result, "Error");
     }
 
     #[test]
     fn test_invalid_input() {
-        let mut calc = Calculator::new().0;
+        let mut calc = Calculator::with_defaults();
 
         // Simulate invalid input
-        calc.update(Message::Input("abc".to_string()));
-        calc.update(Message::Calculate);
-        assert_eq!(calc.result, "");
+        let _ = calc.update(Message::Input("abc".to_string()));
+        let _ = calc.update(Message::Calculate);
+        assert_eq!(calc.result, "Error: unknown variable 'abc'");
+    }
+
+    #[test]
+    fn test_uncertain_keys() {
+        let mut calc = Calculator::with_defaults();
+        calc.input = "3 ± 0.1".to_string();
+        let _ = calc.update(Message::Square);
+        assert_eq!(calc.result, "9.00 ± 0.60");
+
+        // An uncertain operand carries through the operator keys.
+        let _ = calc.update(Message::Input("2 ± 0.1".to_string()));
+        let _ = calc.update(Message::OperatorPressed(Operator::Multiply));
+        let _ = calc.update(Message::Input("5".to_string()));
+        let _ = calc.update(Message::Calculate);
+        assert_eq!(calc.result, "10.00 ± 0.50");
+
+        calc.input = "0".to_string();
+        let _ = calc.update(Message::Reciprocal);
+        assert_eq!(calc.result, "Error");
+
+        calc.input = "100 ± 1".to_string();
+        calc.apply_log_function(LogFunction::Log10);
+        assert_eq!(calc.result, "2.0000 ± 0.0043");
+    }
+
+    #[test]
+    fn test_locale_rewrites_editors() {
+        let mut calc = Calculator::with_defaults();
+        let _ = calc.update(Message::LocaleSelected(locale::Locale::German));
+        calc.matrices[0].cells[0][0] = "1,5".into();
+        calc.polynomial.coefficients[0] = "-2,25".into();
+        let _ = calc.update(Message::LocaleSelected(locale::Locale::English));
+        assert_eq!(calc.matrices[0].cells[0][0], "1.5");
+        assert_eq!(calc.polynomial.coefficients[0], "-2.25");
+    }
+
+    #[test]
+    fn test_key_results_are_rounded() {
+        let mut calc = Calculator::with_defaults();
+        let _ = calc.update(Message::SignificantFiguresToggled(true));
+        calc.input = "1.50".to_string();
+        let _ = calc.update(Message::Square);
+        assert_eq!(calc.result, "2.25");
+        calc.input = "3.0".to_string();
+        let _ = calc.update(Message::Reciprocal);
+        assert_eq!(calc.result, "0.33");
+        calc.input = "2.0".to_string();
+        let _ = calc.update(Message::SquareRoot);
+        assert_eq!(calc.result, "1.4");
     }
 }
-*/
@@ -0,0 +1,464 @@
+use crate::locale::Locale;
+use std::fmt;
+
+/// Largest matrix the grid editor offers.
+pub const MAX_SIZE: usize = 6;
+
+const EPSILON: f64 = 1e-12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    DimensionMismatch {
+        operation: &'static str,
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
+    NotSquare(usize, usize),
+    NotVector(usize, usize),
+    Singular,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DimensionMismatch {
+                operation,
+                lhs,
+                rhs,
+            } => write!(
+                f,
+                "cannot {operation} {}×{} and {}×{} matrices",
+                lhs.0, lhs.1, rhs.0, rhs.1
+            ),
+            Error::NotSquare(rows, cols) => write!(f, "{rows}×{cols} matrix is not square"),
+            Error::NotVector(rows, cols) => write!(f, "{rows}×{cols} matrix is not a vector"),
+            Error::Singular => write!(f, "matrix is singular"),
+        }
+    }
+}
+
+/// A dense, row-major matrix. Vectors are 1×n or n×1 matrices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Self {
+        assert_eq!(data.len(), rows * cols, "matrix data has the wrong length");
+        Self { rows, cols, data }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::new(rows, cols, vec![0.0; rows * cols])
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zeros(size, size);
+        for i in 0..size {
+            matrix[(i, i)] = 1.0;
+        }
+        matrix
+    }
+
+    fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn is_vector(&self) -> bool {
+        self.rows == 1 || self.cols == 1
+    }
+
+    fn zip_with(
+        &self,
+        other: &Matrix,
+        operation: &'static str,
+        f: impl Fn(f64, f64) -> f64,
+    ) -> Result<Matrix, Error> {
+        if self.dimensions() != other.dimensions() {
+            return Err(Error::DimensionMismatch {
+                operation,
+                lhs: self.dimensions(),
+                rhs: other.dimensions(),
+            });
+        }
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(&a, &b)| f(a, b))
+            .collect();
+        Ok(Matrix::new(self.rows, self.cols, data))
+    }
+
+    pub fn add(&self, other: &Matrix) -> Result<Matrix, Error> {
+        self.zip_with(other, "add", |a, b| a + b)
+    }
+
+    pub fn subtract(&self, other: &Matrix) -> Result<Matrix, Error> {
+        self.zip_with(other, "subtract", |a, b| a - b)
+    }
+
+    pub fn scale(&self, factor: f64) -> Matrix {
+        let data = self.data.iter().map(|value| value * factor).collect();
+        Matrix::new(self.rows, self.cols, data)
+    }
+
+    pub fn multiply(&self, other: &Matrix) -> Result<Matrix, Error> {
+        if self.cols != other.rows {
+            return Err(Error::DimensionMismatch {
+                operation: "multiply",
+                lhs: self.dimensions(),
+                rhs: other.dimensions(),
+            });
+        }
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                product[(i, j)] = (0..self.cols).map(|k| self[(i, k)] * other[(k, j)]).sum();
+            }
+        }
+        Ok(product)
+    }
+
+    /// Raises a square matrix to an integer power; negative powers invert first.
+    pub fn power(&self, exponent: i32) -> Result<Matrix, Error> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare(self.rows, self.cols));
+        }
+        let mut base = if exponent < 0 {
+            self.inverse()?
+        } else {
+            self.clone()
+        };
+        let mut exponent = exponent.unsigned_abs();
+        let mut result = Matrix::identity(self.rows);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&base)?;
+            }
+            base = base.multiply(&base)?;
+            exponent >>= 1;
+        }
+        Ok(result)
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut transposed = Matrix::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                transposed[(j, i)] = self[(i, j)];
+            }
+        }
+        transposed
+    }
+
+    /// Determinant by Gaussian elimination with partial pivoting.
+    pub fn determinant(&self) -> Result<f64, Error> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare(self.rows, self.cols));
+        }
+        let mut m = self.clone();
+        let mut determinant = 1.0;
+        for col in 0..m.cols {
+            let pivot = m.pivot_row(col, col);
+            if m[(pivot, col)] == 0.0 {
+                return Ok(0.0);
+            }
+            if pivot != col {
+                m.swap_rows(pivot, col);
+                determinant = -determinant;
+            }
+            determinant *= m[(col, col)];
+            m.eliminate_below(col, col);
+        }
+        Ok(determinant)
+    }
+
+    pub fn inverse(&self) -> Result<Matrix, Error> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare(self.rows, self.cols));
+        }
+        self.solve(&Matrix::identity(self.rows))
+    }
+
+    /// Number of linearly independent rows.
+    pub fn rank(&self) -> usize {
+        let mut m = self.clone();
+        let tolerance = EPSILON * m.norm().max(1.0);
+        let mut rank = 0;
+        for col in 0..m.cols {
+            if rank == m.rows {
+                break;
+            }
+            let pivot = m.pivot_row(rank, col);
+            if m[(pivot, col)].abs() <= tolerance {
+                continue;
+            }
+            m.swap_rows(pivot, rank);
+            m.eliminate_below(rank, col);
+            rank += 1;
+        }
+        rank
+    }
+
+    /// Solves `self · x = rhs` by Gauss–Jordan elimination.
+    pub fn solve(&self, rhs: &Matrix) -> Result<Matrix, Error> {
+        if self.rows != self.cols {
+            return Err(Error::NotSquare(self.rows, self.cols));
+        }
+        if rhs.rows != self.rows {
+            return Err(Error::DimensionMismatch {
+                operation: "solve",
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            });
+        }
+        let n = self.rows;
+        let width = n + rhs.cols;
+        let mut augmented = Matrix::zeros(n, width);
+        for i in 0..n {
+            for j in 0..n {
+                augmented[(i, j)] = self[(i, j)];
+            }
+            for j in 0..rhs.cols {
+                augmented[(i, n + j)] = rhs[(i, j)];
+            }
+        }
+
+        let tolerance = EPSILON * self.norm().max(1.0);
+        for col in 0..n {
+            let pivot = augmented.pivot_row(col, col);
+            if augmented[(pivot, col)].abs() <= tolerance {
+                return Err(Error::Singular);
+            }
+            augmented.swap_rows(pivot, col);
+            let divisor = augmented[(col, col)];
+            for j in 0..width {
+                augmented[(col, j)] /= divisor;
+            }
+            for i in (0..n).filter(|&i| i != col) {
+                let factor = augmented[(i, col)];
+                for j in 0..width {
+                    augmented[(i, j)] -= factor * augmented[(col, j)];
+                }
+            }
+        }
+
+        let mut solution = Matrix::zeros(n, rhs.cols);
+        for i in 0..n {
+            for j in 0..rhs.cols {
+                solution[(i, j)] = augmented[(i, n + j)];
+            }
+        }
+        Ok(solution)
+    }
+
+    pub fn dot(&self, other: &Matrix) -> Result<f64, Error> {
+        if !self.is_vector() {
+            return Err(Error::NotVector(self.rows, self.cols));
+        }
+        if !other.is_vector() {
+            return Err(Error::NotVector(other.rows, other.cols));
+        }
+        if self.data.len() != other.data.len() {
+            return Err(Error::DimensionMismatch {
+                operation: "take the dot product of",
+                lhs: self.dimensions(),
+                rhs: other.dimensions(),
+            });
+        }
+        Ok(self.data.iter().zip(&other.data).map(|(a, b)| a * b).sum())
+    }
+
+    pub fn cross(&self, other: &Matrix) -> Result<Matrix, Error> {
+        match (self.data.as_slice(), other.data.as_slice()) {
+            ([a1, a2, a3], [b1, b2, b3]) if self.is_vector() && other.is_vector() => {
+                let data = vec![a2 * b3 - a3 * b2, a3 * b1 - a1 * b3, a1 * b2 - a2 * b1];
+                Ok(Matrix::new(self.rows, self.cols, data))
+            }
+            _ => Err(Error::DimensionMismatch {
+                operation: "take the cross product of",
+                lhs: self.dimensions(),
+                rhs: other.dimensions(),
+            }),
+        }
+    }
+
+    fn norm(&self) -> f64 {
+        self.data
+            .iter()
+            .fold(0.0, |max, value| value.abs().max(max))
+    }
+
+    fn pivot_row(&self, from_row: usize, col: usize) -> usize {
+        (from_row..self.rows)
+            .max_by(|&a, &b| self[(a, col)].abs().total_cmp(&self[(b, col)].abs()))
+            .unwrap_or(from_row)
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+
+    fn eliminate_below(&mut self, row: usize, col: usize) {
+        for i in row + 1..self.rows {
+            let factor = self[(i, col)] / self[(row, col)];
+            for j in col..self.cols {
+                let value = self[(row, j)];
+                self[(i, j)] -= factor * value;
+            }
+        }
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (row, col): (usize, usize)) -> &f64 {
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f64 {
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..self.rows {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let row: Vec<String> = (0..self.cols).map(|j| self[(i, j)].to_string()).collect();
+            write!(f, "[{}]", row.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Cell contents of a matrix being edited in the grid editor.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub rows: usize,
+    pub cols: usize,
+    pub cells: [[String; MAX_SIZE]; MAX_SIZE],
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            rows: 2,
+            cols: 2,
+            cells: Default::default(),
+        }
+    }
+}
+
+impl Editor {
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        self.rows = rows.clamp(1, MAX_SIZE);
+        self.cols = cols.clamp(1, MAX_SIZE);
+    }
+
+    /// Parses every visible cell, written for `locale`; empty cells count as zero.
+    pub fn matrix(&self, locale: Locale) -> Result<Matrix, (usize, usize, String)> {
+        let mut matrix = Matrix::zeros(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                let cell = self.cells[i][j].trim();
+                if cell.is_empty() {
+                    continue;
+                }
+                matrix[(i, j)] = locale
+                    .delocalize(cell)
+                    .ok()
+                    .and_then(|cell| cell.parse().ok())
+                    .ok_or_else(|| (i + 1, j + 1, cell.to_string()))?;
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: usize, cols: usize, data: &[f64]) -> Matrix {
+        Matrix::new(rows, cols, data.to_vec())
+    }
+
+    #[test]
+    fn multiply() {
+        let a = matrix(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let b = matrix(3, 1, &[1.0, 0.0, -1.0]);
+        assert_eq!(a.multiply(&b), Ok(matrix(2, 1, &[-2.0, -2.0])));
+        assert!(matches!(
+            b.multiply(&a),
+            Err(Error::DimensionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn determinant_and_inverse() {
+        let a = matrix(2, 2, &[4.0, 7.0, 2.0, 6.0]);
+        assert!((a.determinant().unwrap() - 10.0).abs() < 1e-12);
+        let product = a.multiply(&a.inverse().unwrap()).unwrap();
+        assert!(product.subtract(&Matrix::identity(2)).unwrap().norm() < 1e-12);
+        let singular = matrix(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+        assert_eq!(singular.inverse(), Err(Error::Singular));
+        assert_eq!(singular.rank(), 1);
+    }
+
+    #[test]
+    fn solve() {
+        let a = matrix(2, 2, &[2.0, 1.0, 1.0, 3.0]);
+        let b = matrix(2, 1, &[3.0, 5.0]);
+        let x = a.solve(&b).unwrap();
+        assert!(x.subtract(&matrix(2, 1, &[0.8, 1.4])).unwrap().norm() < 1e-12);
+    }
+
+    #[test]
+    fn vectors() {
+        let u = matrix(1, 3, &[1.0, 0.0, 0.0]);
+        let v = matrix(1, 3, &[0.0, 1.0, 0.0]);
+        assert_eq!(u.dot(&v), Ok(0.0));
+        assert_eq!(u.cross(&v), Ok(matrix(1, 3, &[0.0, 0.0, 1.0])));
+    }
+
+    #[test]
+    fn power() {
+        let a = matrix(2, 2, &[1.0, 1.0, 0.0, 1.0]);
+        assert_eq!(a.power(3), Ok(matrix(2, 2, &[1.0, 3.0, 0.0, 1.0])));
+        assert_eq!(a.power(0), Ok(Matrix::identity(2)));
+    }
+
+    #[test]
+    fn editor() {
+        let mut editor = Editor::default();
+        editor.cells[0][0] = "1.5".into();
+        editor.cells[1][1] = "x".into();
+        assert_eq!(editor.matrix(Locale::Plain), Err((2, 2, "x".into())));
+        editor.cells[1][1].clear();
+        assert_eq!(
+            editor.matrix(Locale::Plain),
+            Ok(matrix(2, 2, &[1.5, 0.0, 0.0, 0.0]))
+        );
+        // Cells are read with the locale's decimal separator.
+        assert_eq!(editor.matrix(Locale::German), Err((1, 1, "1.5".into())));
+        editor.cells[0][0] = "1,5".into();
+        assert_eq!(
+            editor.matrix(Locale::German),
+            Ok(matrix(2, 2, &[1.5, 0.0, 0.0, 0.0]))
+        );
+        editor.resize(0, 99);
+        assert_eq!((editor.rows, editor.cols), (1, MAX_SIZE));
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+
+/// Bases that make Miller–Rabin deterministic for every 64-bit integer.
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Primes below this are found by trial division before trying Pollard's rho.
+const TRIAL_DIVISION_LIMIT: u64 = 1000;
+
+/// A prime factorization, primes in ascending order with their exponents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Factorization {
+    pub factors: Vec<(u64, u32)>,
+}
+
+impl Factorization {
+    pub fn value(&self) -> u64 {
+        self.factors
+            .iter()
+            .map(|&(prime, exponent)| prime.pow(exponent))
+            .product()
+    }
+}
+
+impl fmt::Display for Factorization {
+    /// Writes `2^3 · 3 · 7`, which the expression parser reads back as the same number.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+        for (i, &(prime, exponent)) in self.factors.iter().enumerate() {
+            if i > 0 {
+                write!(f, " · ")?;
+            }
+            match exponent {
+                1 => write!(f, "{prime}")?,
+                _ => write!(f, "{prime}^{exponent}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn multiply_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// `base^exponent mod modulus` by repeated squaring.
+pub fn pow_mod(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+    let mut base = base % modulus;
+    let mut result = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply_mod(result, base, modulus);
+        }
+        base = multiply_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Deterministic Miller–Rabin primality test.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    WITNESSES.iter().all(|&witness| {
+        let mut x = pow_mod(witness, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..shift {
+            x = multiply_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Smallest prime greater than `n`, if it fits in 64 bits.
+pub fn next_prime(n: u64) -> Option<u64> {
+    (n.checked_add(1)?..=u64::MAX).find(|&k| is_prime(k))
+}
+
+/// Largest prime less than `n`.
+pub fn prev_prime(n: u64) -> Option<u64> {
+    (2..n).rev().find(|&k| is_prime(k))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Finds a non-trivial factor of the odd composite `n` with Brent's variant of Pollard's rho.
+fn pollard_rho(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut saved) = (2, 2, 2);
+        let mut divisor = 1;
+        let mut length = 1;
+        while divisor == 1 {
+            x = y;
+            for _ in 0..length {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < length && divisor == 1 {
+                saved = y;
+                // Multiply differences together and take one gcd per batch.
+                let mut product = 1;
+                for _ in 0..BATCH.min(length - k) {
+                    y = f(y);
+                    product = multiply_mod(product, x.abs_diff(y), n);
+                }
+                divisor = gcd(product, n);
+                k += BATCH;
+            }
+            length *= 2;
+        }
+        if divisor == n {
+            // The batch overshot; step through it one difference at a time.
+            divisor = 1;
+            while divisor == 1 {
+                saved = f(saved);
+                divisor = gcd(x.abs_diff(saved), n);
+            }
+        }
+        if divisor != n {
+            return divisor;
+        }
+    }
+    unreachable!("some constant c always finds a factor")
+}
+
+/// Prime factorization of `n`; 1 has no factors. `n` must not be zero.
+pub fn factorize(n: u64) -> Factorization {
+    let mut primes = Vec::new();
+    let mut n = n;
+    let mut p = 2;
+    while p < TRIAL_DIVISION_LIMIT && p * p <= n {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+
+    let mut pending = vec![n];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+        } else {
+            let divisor = pollard_rho(m);
+            pending.extend([divisor, m / divisor]);
+        }
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for prime in primes {
+        match factors.last_mut() {
+            Some((last, exponent)) if *last == prime => *exponent += 1,
+            _ => factors.push((prime, 1)),
+        }
+    }
+    Factorization { factors }
+}
+
+/// Euler's totient: how many integers in `1..=n` are coprime to `n`.
+pub fn totient(n: u64) -> u64 {
+    factorize(n)
+        .factors
+        .iter()
+        .map(|&(prime, exponent)| (prime - 1) * prime.pow(exponent - 1))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primes() {
+        let small: Vec<u64> = (0..30).filter(|&n| is_prime(n)).collect();
+        assert_eq!(small, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        // Carmichael numbers fool the Fermat test but not Miller–Rabin.
+        assert!(!is_prime(561));
+        assert!(!is_prime(3_215_031_751));
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert_eq!(next_prime(18_446_744_073_709_551_557), None);
+        assert_eq!(prev_prime(2), None);
+        assert_eq!(prev_prime(100), Some(97));
+    }
+
+    #[test]
+    fn factorization() {
+        let factors = factorize(600_851_475_143);
+        assert_eq!(factors.factors, [(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+        assert_eq!(factors.value(), 600_851_475_143);
+        assert_eq!(factorize(360).to_string(), "2^3 · 3^2 · 5");
+        assert_eq!(factorize(1).to_string(), "1");
+        // Two primes past the trial division limit, left to Pollard's rho.
+        let n = 4_294_967_291 * 4_294_967_279;
+        assert_eq!(
+            factorize(n).factors,
+            [(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+    }
+
+    #[test]
+    fn totient_and_pow_mod() {
+        assert_eq!(totient(1), 1);
+        assert_eq!(totient(36), 12);
+        assert_eq!(pow_mod(2, 64, u64::MAX), 1);
+        assert_eq!(pow_mod(4, 13, 497), 445);
+    }
+}
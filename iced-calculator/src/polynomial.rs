@@ -0,0 +1,381 @@
+use crate::locale::Locale;
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Highest degree the coefficient editor offers.
+pub const MAX_DEGREE: usize = 10;
+
+const MAX_ITERATIONS: usize = 500;
+const POLISH_ITERATIONS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    ZeroPolynomial,
+    NoConvergence,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ZeroPolynomial => write!(f, "every coefficient is zero"),
+            Error::NoConvergence => write!(f, "root finding did not converge"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn real(re: f64) -> Self {
+        Self::new(re, 0.0)
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im == 0.0
+    }
+
+    fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, other: Complex) -> Complex {
+        let denominator = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_real() {
+            write!(f, "{}", self.re)
+        } else if self.im < 0.0 {
+            write!(f, "{} − {}i", self.re, -self.im)
+        } else {
+            write!(f, "{} + {}i", self.re, self.im)
+        }
+    }
+}
+
+/// All roots of the polynomial with `coefficients`, highest power first, repeated
+/// by multiplicity. Real roots come first, in ascending order.
+pub fn roots(coefficients: &[f64]) -> Result<Vec<Complex>, Error> {
+    let first = coefficients
+        .iter()
+        .position(|&c| c != 0.0)
+        .ok_or(Error::ZeroPolynomial)?;
+    let mut coefficients = &coefficients[first..];
+
+    // Each trailing zero coefficient is a factor of x.
+    let mut roots = Vec::new();
+    while let [rest @ .., 0.0] = coefficients {
+        roots.push(Complex::real(0.0));
+        coefficients = rest;
+    }
+
+    let found = match *coefficients {
+        [_] => Vec::new(),
+        [a, b] => vec![Complex::real(-b / a)],
+        [a, b, c] => quadratic(a, b, c),
+        [a, b, c, d] => cubic(b / a, c / a, d / a),
+        _ => durand_kerner(coefficients)?,
+    };
+    roots.extend(
+        found
+            .into_iter()
+            .map(|root| clean(polish(coefficients, root))),
+    );
+
+    roots.sort_by(|x, y| {
+        (!x.is_real())
+            .cmp(&!y.is_real())
+            .then(x.re.total_cmp(&y.re))
+            .then(x.im.total_cmp(&y.im))
+    });
+    Ok(roots)
+}
+
+fn quadratic(a: f64, b: f64, c: f64) -> Vec<Complex> {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        let re = -b / (2.0 * a);
+        let im = (-discriminant).sqrt() / (2.0 * a);
+        return vec![Complex::new(re, im), Complex::new(re, -im)];
+    }
+    // Avoids cancellation between -b and the square root.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        vec![Complex::real(0.0); 2]
+    } else {
+        vec![Complex::real(q / a), Complex::real(c / q)]
+    }
+}
+
+/// Roots of the monic cubic `x³ + a x² + b x + c`, by Cardano's formula when there is
+/// one real root and the trigonometric method when there are three.
+fn cubic(a: f64, b: f64, c: f64) -> Vec<Complex> {
+    let shift = a / 3.0;
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a * a * a / 27.0 - a * b / 3.0 + c;
+    let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+
+    let roots = if discriminant >= 0.0 {
+        let root = discriminant.sqrt();
+        let u = (-q / 2.0 + root).cbrt();
+        let v = (-q / 2.0 - root).cbrt();
+        let im = (u - v) * 3f64.sqrt() / 2.0;
+        vec![
+            Complex::real(u + v),
+            Complex::new(-(u + v) / 2.0, im),
+            Complex::new(-(u + v) / 2.0, -im),
+        ]
+    } else {
+        let r = (-p / 3.0).sqrt();
+        let phi = (-q / (2.0 * r * r * r)).clamp(-1.0, 1.0).acos();
+        (0..3)
+            .map(|k| Complex::real(2.0 * r * (phi / 3.0 - 2.0 * PI * k as f64 / 3.0).cos()))
+            .collect()
+    };
+    roots
+        .into_iter()
+        .map(|root| root - Complex::real(shift))
+        .collect()
+}
+
+/// Finds every root at once, refining each against all the others.
+fn durand_kerner(coefficients: &[f64]) -> Result<Vec<Complex>, Error> {
+    let degree = coefficients.len() - 1;
+    let monic: Vec<f64> = coefficients.iter().map(|c| c / coefficients[0]).collect();
+    // Fujiwara's bound on the size of the roots.
+    let radius = monic[1..]
+        .iter()
+        .enumerate()
+        .map(|(k, c)| 2.0 * c.abs().powf(1.0 / (k + 1) as f64))
+        .fold(0.0, f64::max);
+    // Spread around that circle, off the real axis and slightly irregular.
+    let mut roots: Vec<Complex> = (0..degree)
+        .map(|k| {
+            let angle = 2.0 * PI * k as f64 / degree as f64 + 0.4;
+            Complex::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut change: f64 = 0.0;
+        for k in 0..degree {
+            let mut denominator = Complex::real(1.0);
+            for (j, &other) in roots.iter().enumerate() {
+                if j != k {
+                    denominator = denominator * (roots[k] - other);
+                }
+            }
+            let step = evaluate(&monic, roots[k]) / denominator;
+            roots[k] = roots[k] - step;
+            change = change.max(step.norm() / roots[k].norm().max(1.0));
+        }
+        if change < 1e-14 {
+            break;
+        }
+    }
+    // Repeated roots converge slowly, so accept whatever was reached if it is finite.
+    if roots
+        .iter()
+        .all(|root| root.re.is_finite() && root.im.is_finite())
+    {
+        Ok(roots)
+    } else {
+        Err(Error::NoConvergence)
+    }
+}
+
+/// Horner's rule, returning the value and the derivative.
+fn evaluate_with_derivative(coefficients: &[f64], z: Complex) -> (Complex, Complex) {
+    let mut value = Complex::default();
+    let mut derivative = Complex::default();
+    for &c in coefficients {
+        derivative = derivative * z + value;
+        value = value * z + Complex::real(c);
+    }
+    (value, derivative)
+}
+
+fn evaluate(coefficients: &[f64], z: Complex) -> Complex {
+    evaluate_with_derivative(coefficients, z).0
+}
+
+/// A few Newton steps against the original coefficients, kept only if they help.
+fn polish(coefficients: &[f64], mut root: Complex) -> Complex {
+    for _ in 0..POLISH_ITERATIONS {
+        let (value, derivative) = evaluate_with_derivative(coefficients, root);
+        if derivative.norm() == 0.0 {
+            break;
+        }
+        let next = root - value / derivative;
+        let improved = evaluate(coefficients, next).norm() < value.norm();
+        if !improved {
+            break;
+        }
+        root = next;
+    }
+    root
+}
+
+/// Drops imaginary parts that are only rounding noise.
+fn clean(root: Complex) -> Complex {
+    // Adding zero also turns -0 into 0.
+    if root.im.abs() <= 1e-10 * root.norm().max(1.0) {
+        Complex::real(root.re + 0.0)
+    } else {
+        Complex::new(root.re + 0.0, root.im)
+    }
+}
+
+/// Coefficient cells of the polynomial being edited, indexed by power.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub degree: usize,
+    pub coefficients: [String; MAX_DEGREE + 1],
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            degree: 2,
+            coefficients: Default::default(),
+        }
+    }
+}
+
+impl Editor {
+    pub fn set_degree(&mut self, degree: usize) {
+        self.degree = degree.clamp(1, MAX_DEGREE);
+    }
+
+    /// Parses the visible cells, written for `locale`, highest power first; empty cells
+    /// count as zero.
+    pub fn coefficients(&self, locale: Locale) -> Result<Vec<f64>, (usize, String)> {
+        (0..=self.degree)
+            .rev()
+            .map(|power| {
+                let cell = self.coefficients[power].trim();
+                if cell.is_empty() {
+                    Ok(0.0)
+                } else {
+                    locale
+                        .delocalize(cell)
+                        .ok()
+                        .and_then(|cell| cell.parse().ok())
+                        .ok_or_else(|| (power, cell.to_string()))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(coefficients: &[f64], expected: &[Complex]) {
+        let found = roots(coefficients).unwrap();
+        assert_eq!(found.len(), expected.len(), "{found:?}");
+        for (root, expected) in found.iter().zip(expected) {
+            assert!((*root - *expected).norm() < 1e-9, "{found:?}");
+        }
+    }
+
+    #[test]
+    fn closed_forms() {
+        assert_roots(&[2.0, -4.0], &[Complex::real(2.0)]);
+        assert_roots(&[1.0, -3.0, 2.0], &[Complex::real(1.0), Complex::real(2.0)]);
+        assert_roots(
+            &[1.0, 0.0, 1.0],
+            &[Complex::new(0.0, -1.0), Complex::new(0.0, 1.0)],
+        );
+        assert_roots(
+            &[1.0, -6.0, 11.0, -6.0],
+            &[Complex::real(1.0), Complex::real(2.0), Complex::real(3.0)],
+        );
+        // x³ - x, whose root at zero comes from the trailing coefficient.
+        assert_roots(
+            &[1.0, 0.0, -1.0, 0.0],
+            &[Complex::real(-1.0), Complex::real(0.0), Complex::real(1.0)],
+        );
+    }
+
+    #[test]
+    fn durand_kerner() {
+        assert_roots(
+            &[1.0, 0.0, 0.0, 0.0, -1.0],
+            &[
+                Complex::real(-1.0),
+                Complex::real(1.0),
+                Complex::new(0.0, -1.0),
+                Complex::new(0.0, 1.0),
+            ],
+        );
+        // (x - 1)(x - 2)(x - 3)(x - 4)(x - 5)
+        assert_roots(
+            &[1.0, -15.0, 85.0, -225.0, 274.0, -120.0],
+            &(1..=5).map(|x| Complex::real(x as f64)).collect::<Vec<_>>(),
+        );
+        assert_eq!(roots(&[0.0, 0.0]), Err(Error::ZeroPolynomial));
+    }
+
+    #[test]
+    fn editor() {
+        let mut editor = Editor::default();
+        editor.coefficients[2] = "1".into();
+        editor.coefficients[0] = " -2.5 ".into();
+        assert_eq!(editor.coefficients(Locale::Plain), Ok(vec![1.0, 0.0, -2.5]));
+        editor.coefficients[0] = "-2,5".into();
+        assert_eq!(
+            editor.coefficients(Locale::French),
+            Ok(vec![1.0, 0.0, -2.5])
+        );
+        editor.coefficients[1] = "two".into();
+        assert_eq!(editor.coefficients(Locale::Plain), Err((1, "two".into())));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{Calculator, Message};
+use iced::Task;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const EXTENSION: &str = "replay";
+
+/// Starts a line checking the calculator's result instead of sending a message.
+const EXPECT: &str = "expect ";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(message) => write!(f, "{message}"),
+            Error::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+/// One line of a script.
+///
+/// Messages are written one per line as JSON, such as `{"input": "12"}` or
+/// `"calculate"`. A line `expect 42` checks the result shown at that point, and
+/// blank lines and lines starting with `#` are skipped.
+#[derive(Debug, Clone)]
+pub enum Step {
+    Send(Message),
+    Expect { line: usize, result: String },
+}
+
+/// How a replay went.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub messages: usize,
+    pub expectations: usize,
+    pub failures: Vec<String>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Replayed {} messages", self.messages)?;
+        match self.failures.len() {
+            0 if self.expectations == 0 => Ok(()),
+            0 => write!(f, ", all {} expectations met", self.expectations),
+            failed => write!(
+                f,
+                ", {failed} of {} expectations failed: {}",
+                self.expectations,
+                self.failures.join("; ")
+            ),
+        }
+    }
+}
+
+/// Whether a message can be written to a script; window events and editor actions
+/// cannot, and neither can the recording controls themselves.
+pub fn is_recordable(message: &Message) -> bool {
+    serde_json::to_string(message).is_ok()
+}
+
+pub fn parse(text: &str) -> Result<Vec<Step>, Error> {
+    let mut steps = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let step = match line.strip_prefix(EXPECT) {
+            Some(result) => Step::Expect {
+                line: i + 1,
+                result: result.trim().to_string(),
+            },
+            None => Step::Send(serde_json::from_str(line).map_err(|error| Error::Parse {
+                line: i + 1,
+                message: error.to_string(),
+            })?),
+        };
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+/// Writes recorded messages as a script, one per line.
+pub fn write(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .filter_map(|message| serde_json::to_string(message).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+pub fn with_extension(path: &str) -> PathBuf {
+    let path = PathBuf::from(path.trim());
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension(EXTENSION)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<Step>, Error> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| Error::Io(format!("cannot read {}: {error}", path.display())))?;
+    parse(&text)
+}
+
+pub fn save(path: &Path, messages: &[Message]) -> Result<(), Error> {
+    std::fs::write(path, write(messages))
+        .map_err(|error| Error::Io(format!("cannot write {}: {error}", path.display())))
+}
+
+/// Sends each message to `calculator` in turn, checking every expectation against
+/// the result shown at that point.
+pub fn replay(calculator: &mut Calculator, steps: Vec<Step>) -> (Report, Task<Message>) {
+    let mut report = Report::default();
+    let mut tasks = Vec::new();
+    for step in steps {
+        match step {
+            Step::Send(message) => {
+                report.messages += 1;
+                tasks.push(calculator.update(message));
+            }
+            Step::Expect { line, result } => {
+                report.expectations += 1;
+                if calculator.result != result {
+                    report.failures.push(format!(
+                        "line {line}: expected '{result}', got '{}'",
+                        calculator.result
+                    ));
+                }
+            }
+        }
+    }
+    (report, Task::batch(tasks))
+}
+
+/// Replays a script without opening a window, as `iced-calculator --replay <file>`.
+///
+/// Starts from the bundled defaults rather than the user's rate table and keypad.
+/// Prints the report and the final result; returns whether every expectation held.
+pub fn run_headless(path: &Path) -> bool {
+    let steps = match load(path) {
+        Ok(steps) => steps,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            return false;
+        }
+    };
+    let mut calculator = Calculator::with_defaults();
+    let (report, _) = replay(&mut calculator, steps);
+    println!("{report}");
+    println!("{}", calculator.result);
+    report.failures.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expectations() {
+        let steps =
+            parse("{\"input\": \"6\"}\n\"square\"\n# comment\nexpect 36\nexpect 7\n").unwrap();
+        let (report, _) = replay(&mut Calculator::with_defaults(), steps);
+        assert_eq!(report.messages, 2);
+        assert_eq!(report.expectations, 2);
+        assert_eq!(report.failures, ["line 5: expected '7', got '36'"]);
+    }
+
+    #[test]
+    fn headless_replay_saves_nothing() {
+        let steps = parse("\"rates-saved\"\n").unwrap();
+        let mut calculator = Calculator::with_defaults();
+        calculator.rates = crate::currency::Editor {
+            base: "EUR".into(),
+            date: "2026-10-01".into(),
+            rows: vec![("USD".into(), "1.08".into())],
+        };
+        let _ = replay(&mut calculator, steps);
+        assert_eq!(calculator.rates_status, "Error: nowhere to save the rates");
+    }
+
+    #[test]
+    fn parse_error_has_line() {
+        let error = parse("\"calculate\"\n{\"no-such-message\": 1}").unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn out_of_range_indexes_are_ignored() {
+        let script = r#"
+            {"matrix-selected": 7}
+            {"matrix-cell-changed": [9, 9, "1"]}
+            {"rate-code-changed": [3, "USD"]}
+            {"rate-value-changed": [3, "1.1"]}
+            {"rate-removed": 3}
+            {"coefficient-changed": [42, "1"]}
+            {"input-changed": "1 + 1"}
+            "calculate"
+            expect 2
+        "#;
+        let (report, _) = replay(&mut Calculator::with_defaults(), parse(script).unwrap());
+        assert_eq!(report.messages, 8);
+        assert!(report.failures.is_empty(), "{report}");
+    }
+}
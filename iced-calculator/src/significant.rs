@@ -0,0 +1,142 @@
+/// Significant figures of a number as typed: `0.00120` has 3, `1200` has 2, `1200.`
+/// has 4 and `1.23e4` has 3. Zero carries no precision and gives `None`.
+pub fn count(literal: &str) -> Option<u32> {
+    let mantissa = literal.split(['e', 'E']).next()?;
+    if !mantissa.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0');
+    // Trailing zeros of a whole number only count when a decimal point says so.
+    let significant = if mantissa.contains('.') {
+        significant
+    } else {
+        significant.trim_end_matches('0')
+    };
+    (!significant.is_empty()).then_some(significant.len() as u32)
+}
+
+/// The fewest significant figures among the numbers in `input`, which limits how
+/// precise a result computed from them can be.
+///
+/// Digits that are part of a name, such as `sqrt2` or `m_e`, are not numbers.
+pub fn least(input: &str) -> Option<u32> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut least: Option<u32> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let in_name = i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+        if !(chars[i].is_ascii_digit() || chars[i] == '.') || in_name {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+            i += 1;
+        }
+        let literal: String = chars[start..i].iter().collect();
+        if let Some(figures) = count(&literal) {
+            least = Some(least.map_or(figures, |least| least.min(figures)));
+        }
+        // Skip an exponent so its digits are not read as another number.
+        if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+            let mut j = i + 1;
+            if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                j += 1;
+            }
+            if j < chars.len() && chars[j].is_ascii_digit() {
+                i = j;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+        }
+    }
+    least
+}
+
+/// `value` rounded to `figures` significant figures.
+///
+/// Plain notation is used unless it would leave it unclear which zeros count, so the
+/// result reads back with the same number of figures.
+pub fn round(value: f64, figures: u32) -> String {
+    if value == 0.0 || !value.is_finite() || figures == 0 {
+        return value.to_string();
+    }
+    let scientific = format!("{:.*e}", figures as usize - 1, value);
+    let exponent: i32 = scientific
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse().ok())
+        .unwrap_or_default();
+    if exponent >= figures as i32 || exponent < -4 {
+        return scientific;
+    }
+    let decimals = (figures as i32 - 1 - exponent).max(0) as usize;
+    let plain = format!("{value:.decimals$}");
+    // `120` with three figures is written `120.` so the last zero counts.
+    if decimals == 0 && plain.ends_with('0') {
+        format!("{plain}.")
+    } else {
+        plain
+    }
+}
+
+/// Writes the digits and minus sign of an exponent as superscripts, as in `10⁻⁴`.
+pub fn superscript(exponent: &str) -> String {
+    exponent
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '+' => '⁺',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            '9' => '⁹',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        assert_eq!(count("1200."), Some(4));
+        assert_eq!(count("1200"), Some(2));
+        assert_eq!(count("0.00120"), Some(3));
+        assert_eq!(count("1.23e4"), Some(3));
+        assert_eq!(count("0"), None);
+        assert_eq!(count("abc"), None);
+    }
+
+    #[test]
+    fn least_skips_names_and_exponents() {
+        assert_eq!(least("2.50 * 3.14159"), Some(3));
+        assert_eq!(least("1.5e10 * 2.000"), Some(2));
+        assert_eq!(least("sqrt2 * 4.00"), Some(3));
+        assert_eq!(least("m_e * 1.0"), Some(2));
+        assert_eq!(least("pi"), None);
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(round(7.853975, 3), "7.85");
+        assert_eq!(round(119.6, 3), "120.");
+        assert_eq!(round(0.000123456, 2), "0.00012");
+        assert_eq!(round(123456.0, 2), "1.2e5");
+        assert_eq!(round(0.0000123, 2), "1.2e-5");
+    }
+
+    #[test]
+    fn superscripts() {
+        assert_eq!(superscript("-12"), "⁻¹²");
+    }
+}
@@ -0,0 +1,129 @@
+use iced::Size;
+
+/// Keypad cells across when the scientific keys sit beside the basic ones.
+const FULL_COLUMNS: u16 = 8;
+/// Keypad cells across when only the basic keys fit.
+const NARROW_COLUMNS: u16 = 4;
+
+const MIN_KEY_WIDTH: f32 = 56.0;
+const MAX_KEY_WIDTH: f32 = 120.0;
+const MIN_KEY_HEIGHT: f32 = 36.0;
+const MAX_KEY_HEIGHT: f32 = 80.0;
+/// Key width the side panel leaves room for.
+const COMFORTABLE_KEY_WIDTH: f32 = 75.0;
+
+/// Width of the history panel shown beside the calculator on wide windows.
+pub const SIDE_PANEL_WIDTH: f32 = 300.0;
+
+/// Space kept free around the calculator.
+const MARGIN: f32 = 20.0;
+/// Space between the calculator and the history panel.
+pub const GAP: f32 = 20.0;
+const SPACING: f32 = 2.0;
+
+/// Rows of keys plus the mode bar, result, input and some room for the mode's panel.
+const ROWS: f32 = 14.0;
+
+/// Sizes of the calculator's parts, worked out from the window size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sizing {
+    /// Keypad cells across.
+    pub columns: u16,
+    pub key_width: f32,
+    pub key_height: f32,
+    pub text_size: f32,
+    /// The scientific and integer keys are hidden in a drawer to save width.
+    pub drawer: bool,
+    /// There is room for the history panel.
+    pub side_panel: bool,
+}
+
+impl Sizing {
+    pub fn new(window: Size) -> Self {
+        let available = window.width - 2.0 * MARGIN;
+        let drawer = available < FULL_COLUMNS as f32 * MIN_KEY_WIDTH;
+        let columns = if drawer { NARROW_COLUMNS } else { FULL_COLUMNS };
+        let side_panel = !drawer
+            && available >= FULL_COLUMNS as f32 * COMFORTABLE_KEY_WIDTH + SIDE_PANEL_WIDTH + GAP;
+        let keypad_width = if side_panel {
+            available - SIDE_PANEL_WIDTH - GAP
+        } else {
+            available
+        };
+
+        let key_width = (keypad_width / columns as f32).clamp(MIN_KEY_WIDTH, MAX_KEY_WIDTH);
+        // Keys keep roughly a 3:2 shape unless the window is too short for that.
+        let key_height = (key_width * 2.0 / 3.0)
+            .min((window.height - 2.0 * MARGIN) / ROWS)
+            .clamp(MIN_KEY_HEIGHT, MAX_KEY_HEIGHT);
+        Self {
+            columns,
+            key_width,
+            key_height,
+            text_size: (key_height * 0.48).round(),
+            drawer,
+            side_panel,
+        }
+    }
+
+    /// Width of the calculator column, which matches the keypads.
+    pub fn width(&self) -> f32 {
+        self.keys(self.columns as f32) + SPACING
+    }
+
+    /// Width of `count` keypad cells.
+    pub fn keys(&self, count: f32) -> f32 {
+        count * self.key_width
+    }
+
+    /// Width of one of `count` equal cells spaced across the calculator column.
+    pub fn share(&self, count: usize) -> f32 {
+        (self.width() - SPACING * (count - 1) as f32) / count as f32
+    }
+}
+
+impl Default for Sizing {
+    fn default() -> Self {
+        Self::new(iced::window::Settings::default().size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizing(width: f32, height: f32) -> Sizing {
+        Sizing::new(Size::new(width, height))
+    }
+
+    #[test]
+    fn breakpoints() {
+        // Eight of the narrowest keys need 448 pixels between the margins.
+        let narrow = sizing(487.0, 700.0);
+        assert!(narrow.drawer && !narrow.side_panel);
+        assert_eq!(narrow.columns, NARROW_COLUMNS);
+        let full = sizing(488.0, 700.0);
+        assert!(!full.drawer && !full.side_panel);
+        assert_eq!(full.columns, FULL_COLUMNS);
+        assert_eq!(full.key_width, MIN_KEY_WIDTH);
+        // The history panel needs comfortable keys beside it.
+        assert!(!sizing(959.0, 700.0).side_panel);
+        let wide = sizing(960.0, 700.0);
+        assert!(wide.side_panel);
+        assert_eq!(wide.key_width, COMFORTABLE_KEY_WIDTH);
+    }
+
+    #[test]
+    fn key_size_limits() {
+        let huge = sizing(4000.0, 3000.0);
+        assert_eq!(
+            (huge.key_width, huge.key_height),
+            (MAX_KEY_WIDTH, MAX_KEY_HEIGHT)
+        );
+        // Short windows squash the keys, but only down to the smallest height.
+        let short = sizing(800.0, 200.0);
+        assert_eq!(short.key_height, MIN_KEY_HEIGHT);
+        assert_eq!(short.text_size, (MIN_KEY_HEIGHT * 0.48).round());
+        assert_eq!(short.width(), short.keys(FULL_COLUMNS as f32) + SPACING);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::expr::{self, Context, Expr};
+use std::fmt;
+
+const TOLERANCE: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 100;
+const MAX_BRACKET_EXPANSIONS: usize = 60;
+
+/// The variable an equation is solved for.
+pub const UNKNOWN: &str = "x";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Expression(expr::Error),
+    TooManyEquals,
+    InvalidStart(String),
+    NoSignChange { a: f64, b: f64 },
+    NoBracket,
+    NotFinite(f64),
+    Discontinuity(f64),
+    NoConvergence { iterations: usize, best: f64 },
+}
+
+impl From<expr::Error> for Error {
+    fn from(error: expr::Error) -> Self {
+        Error::Expression(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Expression(error) => write!(f, "{error}"),
+            Error::TooManyEquals => write!(f, "an equation may contain only one '='"),
+            Error::InvalidStart(start) => {
+                write!(
+                    f,
+                    "start must be a guess or a bracket 'a, b', got '{start}'"
+                )
+            }
+            Error::NoSignChange { a, b } => {
+                write!(
+                    f,
+                    "f({a}) and f({b}) have the same sign; no root is bracketed"
+                )
+            }
+            Error::NoBracket => write!(f, "no sign change found near the initial guess"),
+            Error::NotFinite(x) => write!(f, "equation is not finite at {UNKNOWN} = {x}"),
+            Error::Discontinuity(x) => write!(
+                f,
+                "sign change at {UNKNOWN} ≈ {x} is a discontinuity, not a root"
+            ),
+            Error::NoConvergence { iterations, best } => write!(
+                f,
+                "did not converge after {iterations} iterations (best {UNKNOWN} ≈ {best})"
+            ),
+        }
+    }
+}
+
+/// Where the search for a root starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Start {
+    Guess(f64),
+    Bracket(f64, f64),
+}
+
+impl Start {
+    /// Parses an empty string, a single guess `x0` or a bracket `a, b`.
+    pub fn parse(input: &str, context: &Context) -> Result<Self, Error> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Start::Guess(0.0));
+        }
+        let parts: Vec<&str> = input.split([',', ';']).collect();
+        match parts.as_slice() {
+            [guess] => Ok(Start::Guess(expr::evaluate(guess, context)?.number()?)),
+            [a, b] => Ok(Start::Bracket(
+                expr::evaluate(a, context)?.number()?,
+                expr::evaluate(b, context)?.number()?,
+            )),
+            _ => Err(Error::InvalidStart(input.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Newton,
+    Brent,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Newton => write!(f, "Newton"),
+            Method::Brent => write!(f, "Brent"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Root {
+    pub x: f64,
+    pub residual: f64,
+    pub iterations: usize,
+    pub method: Method,
+}
+
+impl fmt::Display for Root {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{UNKNOWN} = {} ({}, {} iterations)",
+            self.x, self.method, self.iterations
+        )
+    }
+}
+
+/// An equation `f(x) = g(x)`, rearranged as `f(x) - g(x) = 0`.
+pub struct Equation {
+    lhs: Expr,
+    rhs: Option<Expr>,
+    context: Context,
+}
+
+impl Equation {
+    /// Parses `f(x) = g(x)`; without an `=` the expression is solved for zero.
+    pub fn parse(input: &str, context: Context) -> Result<Self, Error> {
+        let mut sides = input.split('=');
+        let lhs = expr::parse(sides.next().unwrap_or_default())?;
+        let rhs = sides.next().map(expr::parse).transpose()?;
+        if sides.next().is_some() {
+            return Err(Error::TooManyEquals);
+        }
+        Ok(Self { lhs, rhs, context })
+    }
+
+    fn residual(&mut self, x: f64) -> Result<f64, Error> {
+        self.context.set(UNKNOWN, x);
+        let lhs = self.lhs.eval_number(&self.context)?;
+        let value = match &self.rhs {
+            Some(rhs) => lhs - rhs.eval_number(&self.context)?,
+            None => lhs,
+        };
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(Error::NotFinite(x))
+        }
+    }
+
+    pub fn solve(&mut self, start: Start) -> Result<Root, Error> {
+        match start {
+            Start::Bracket(a, b) => self.brent(a, b),
+            Start::Guess(x0) => match self.newton(x0) {
+                Ok(root) => Ok(root),
+                Err(Error::Expression(error)) => Err(Error::Expression(error)),
+                Err(_) => {
+                    let (a, b) = self.find_bracket(x0)?;
+                    self.brent(a, b)
+                }
+            },
+        }
+    }
+
+    /// Newton's method with a central-difference derivative.
+    fn newton(&mut self, x0: f64) -> Result<Root, Error> {
+        let mut x = x0;
+        for iteration in 1..=MAX_ITERATIONS {
+            let fx = self.residual(x)?;
+            if fx == 0.0 {
+                return Ok(self.root(x, fx, iteration, Method::Newton));
+            }
+            let h = 1e-7 * x.abs().max(1.0);
+            let derivative = (self.residual(x + h)? - self.residual(x - h)?) / (2.0 * h);
+            if derivative == 0.0 || !derivative.is_finite() {
+                break;
+            }
+            let step = fx / derivative;
+            x -= step;
+            if !x.is_finite() {
+                break;
+            }
+            if step.abs() <= TOLERANCE * x.abs().max(1.0) {
+                let fx = self.residual(x)?;
+                // A tiny step with a large residual is a stationary point, not a root.
+                if fx.abs() <= 1e-9 * (1.0 + x.abs()) {
+                    return Ok(self.root(x, fx, iteration, Method::Newton));
+                }
+                break;
+            }
+        }
+        Err(Error::NoConvergence {
+            iterations: MAX_ITERATIONS,
+            best: x,
+        })
+    }
+
+    /// Expands geometrically outwards from `x0` until the residual changes sign.
+    ///
+    /// Points where the equation is not finite are skipped, so a guess outside the
+    /// domain (e.g. `sqrt(x) = 3` from `-5`) can still walk into it.
+    fn find_bracket(&mut self, x0: f64) -> Result<(f64, f64), Error> {
+        let f0 = self.residual(x0).ok();
+        let mut step = 0.1 * x0.abs().max(1.0);
+        let mut left = f0.map(|f| (x0, f));
+        let mut right = left;
+
+        for _ in 0..MAX_BRACKET_EXPANSIONS {
+            for (side, next) in [(&mut left, x0 - step), (&mut right, x0 + step)] {
+                let Ok(f) = self.residual(next) else {
+                    continue;
+                };
+                match *side {
+                    Some((previous, f_previous)) if f.signum() != f_previous.signum() => {
+                        return Ok((previous.min(next), previous.max(next)));
+                    }
+                    _ => *side = Some((next, f)),
+                }
+            }
+            step *= 1.6;
+        }
+        Err(Error::NoBracket)
+    }
+
+    /// Brent's method: bisection safeguarded inverse quadratic interpolation.
+    fn brent(&mut self, a: f64, b: f64) -> Result<Root, Error> {
+        let (mut a, mut b) = (a, b);
+        let (mut fa, mut fb) = (self.residual(a)?, self.residual(b)?);
+        if fa == 0.0 {
+            return Ok(self.root(a, fa, 0, Method::Brent));
+        }
+        if fb == 0.0 {
+            return Ok(self.root(b, fb, 0, Method::Brent));
+        }
+        if fa.signum() == fb.signum() {
+            return Err(Error::NoSignChange { a, b });
+        }
+        let scale = fa.abs().max(fb.abs()).max(1.0);
+
+        let (mut c, mut fc) = (a, fa);
+        let mut d = b - a;
+        let mut e = d;
+
+        for iteration in 1..=MAX_ITERATIONS {
+            if fb.signum() == fc.signum() {
+                (c, fc) = (a, fa);
+                d = b - a;
+                e = d;
+            }
+            if fc.abs() < fb.abs() {
+                (a, fa) = (b, fb);
+                (b, fb) = (c, fc);
+                (c, fc) = (a, fa);
+            }
+
+            let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * TOLERANCE;
+            let midpoint = 0.5 * (c - b);
+            if midpoint.abs() <= tolerance || fb == 0.0 {
+                if fb.abs() > 1e-6 * scale {
+                    return Err(Error::Discontinuity(b));
+                }
+                return Ok(self.root(b, fb, iteration, Method::Brent));
+            }
+
+            if e.abs() >= tolerance && fa.abs() > fb.abs() {
+                let s = fb / fa;
+                let (mut p, mut q) = if a == c {
+                    (2.0 * midpoint * s, 1.0 - s)
+                } else {
+                    let q = fa / fc;
+                    let r = fb / fc;
+                    (
+                        s * (2.0 * midpoint * q * (q - r) - (b - a) * (r - 1.0)),
+                        (q - 1.0) * (r - 1.0) * (s - 1.0),
+                    )
+                };
+                if p > 0.0 {
+                    q = -q;
+                } else {
+                    p = -p;
+                }
+                if 2.0 * p < (3.0 * midpoint * q - (tolerance * q).abs()).min((e * q).abs()) {
+                    e = d;
+                    d = p / q;
+                } else {
+                    d = midpoint;
+                    e = d;
+                }
+            } else {
+                d = midpoint;
+                e = d;
+            }
+
+            (a, fa) = (b, fb);
+            b += if d.abs() > tolerance {
+                d
+            } else {
+                tolerance.copysign(midpoint)
+            };
+            fb = self.residual(b)?;
+        }
+
+        Err(Error::NoConvergence {
+            iterations: MAX_ITERATIONS,
+            best: b,
+        })
+    }
+
+    fn root(&self, x: f64, residual: f64, iterations: usize, method: Method) -> Root {
+        Root {
+            x,
+            residual,
+            iterations,
+            method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(equation: &str, start: Start) -> Root {
+        Equation::parse(equation, Context::default())
+            .and_then(|mut equation| equation.solve(start))
+            .unwrap()
+    }
+
+    #[test]
+    fn newton() {
+        let root = solve("x^2 = 2", Start::Guess(1.0));
+        assert_eq!(root.method, Method::Newton);
+        assert!((root.x - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn fixed_point() {
+        let root = solve("cos(x) = x", Start::Guess(0.0));
+        assert!((root.x - 0.739_085_133_215_160_6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn brent_in_bracket() {
+        let root = solve("x^3 - x - 2", Start::Bracket(1.0, 2.0));
+        assert_eq!(root.method, Method::Brent);
+        assert!(root.residual.abs() < 1e-9);
+    }
+
+    #[test]
+    fn falls_back_to_brent_at_a_stationary_point() {
+        let root = solve("x^3 - 8", Start::Guess(0.0));
+        assert!((root.x - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn start() {
+        let context = Context::default();
+        assert_eq!(Start::parse("", &context), Ok(Start::Guess(0.0)));
+        assert_eq!(Start::parse("1, 2", &context), Ok(Start::Bracket(1.0, 2.0)));
+        assert!(Start::parse("1, 2, 3", &context).is_err());
+    }
+
+    #[test]
+    fn too_many_equals() {
+        assert!(matches!(
+            Equation::parse("x = 1 = 2", Context::default()),
+            Err(Error::TooManyEquals)
+        ));
+    }
+}
@@ -0,0 +1,634 @@
+use crate::constants;
+use crate::expr::Expr;
+use crate::locale::Locale;
+use crate::Operator;
+use iced::widget::canvas::{self, Frame, Geometry, Path, Stroke, Text};
+use iced::{alignment, mouse, widget::text::LineHeight, Point, Rectangle, Renderer, Theme, Vector};
+
+/// Advance of a monospace glyph, as a fraction of the font size.
+const GLYPH_WIDTH: f32 = 0.6;
+/// Extent of a glyph above and below the baseline, as fractions of the font size.
+const ASCENT: f32 = 0.8;
+const DESCENT: f32 = 0.2;
+/// Height of fraction bars, which line up with the middle of `+` and `−`.
+const AXIS: f32 = 0.3;
+/// Exponents, subscripts and radical indices are drawn this much smaller.
+const SCRIPT_SCALE: f32 = 0.7;
+/// Formulas too wide for the canvas are shrunk, but not below this size.
+const SMALLEST_SIZE: f32 = 10.0;
+const PADDING: f32 = 4.0;
+
+/// Something to draw, positioned relative to the left end of the baseline.
+#[derive(Debug, Clone, PartialEq)]
+enum Primitive {
+    /// Text whose baseline starts at `position`.
+    Text {
+        position: Point,
+        content: String,
+        size: f32,
+    },
+    Polyline(Vec<Point>),
+    Curve {
+        from: Point,
+        control: Point,
+        to: Point,
+    },
+}
+
+impl Primitive {
+    fn translate(self, offset: Vector) -> Self {
+        match self {
+            Primitive::Text {
+                position,
+                content,
+                size,
+            } => Primitive::Text {
+                position: position + offset,
+                content,
+                size,
+            },
+            Primitive::Polyline(points) => {
+                Primitive::Polyline(points.into_iter().map(|point| point + offset).collect())
+            }
+            Primitive::Curve { from, control, to } => Primitive::Curve {
+                from: from + offset,
+                control: control + offset,
+                to: to + offset,
+            },
+        }
+    }
+}
+
+/// A typeset box: how far it reaches right, up and down from its baseline origin.
+#[derive(Debug, Clone, Default)]
+struct Layout {
+    width: f32,
+    ascent: f32,
+    descent: f32,
+    primitives: Vec<Primitive>,
+}
+
+impl Layout {
+    fn text(content: impl Into<String>, size: f32) -> Self {
+        let content = content.into();
+        Self {
+            width: content.chars().count() as f32 * GLYPH_WIDTH * size,
+            ascent: ASCENT * size,
+            descent: DESCENT * size,
+            primitives: vec![Primitive::Text {
+                position: Point::ORIGIN,
+                content,
+                size,
+            }],
+        }
+    }
+
+    fn space(width: f32) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// Draws `other` with its origin at `offset`, leaving the box to the caller.
+    fn place(&mut self, other: Layout, offset: Vector) {
+        self.primitives.extend(
+            other
+                .primitives
+                .into_iter()
+                .map(|primitive| primitive.translate(offset)),
+        );
+    }
+
+    fn row(items: impl IntoIterator<Item = Layout>) -> Self {
+        let mut row = Self::default();
+        for item in items {
+            row.ascent = row.ascent.max(item.ascent);
+            row.descent = row.descent.max(item.descent);
+            let x = row.width;
+            row.width += item.width;
+            row.place(item, Vector::new(x, 0.0));
+        }
+        row
+    }
+
+    fn fraction(numerator: Layout, denominator: Layout, size: f32) -> Self {
+        let gap = 0.15 * size;
+        let width = numerator.width.max(denominator.width) + 0.2 * size;
+        let axis = -AXIS * size;
+        let mut fraction = Self {
+            width,
+            ascent: AXIS * size + gap + numerator.descent + numerator.ascent,
+            descent: gap + denominator.ascent + denominator.descent - AXIS * size,
+            primitives: vec![Primitive::Polyline(vec![
+                Point::new(0.0, axis),
+                Point::new(width, axis),
+            ])],
+        };
+        let numerator_offset = Vector::new(
+            (width - numerator.width) / 2.0,
+            axis - gap - numerator.descent,
+        );
+        let denominator_offset = Vector::new(
+            (width - denominator.width) / 2.0,
+            axis + gap + denominator.ascent,
+        );
+        fraction.place(numerator, numerator_offset);
+        fraction.place(denominator, denominator_offset);
+        fraction
+    }
+
+    /// `base` with `exponent`, already at script size, raised to its upper right.
+    fn power(base: Layout, exponent: Layout, size: f32) -> Self {
+        let raise = (0.6 * base.ascent).max(0.45 * size);
+        let mut power = Self {
+            width: base.width + exponent.width,
+            ascent: base.ascent.max(raise + exponent.ascent),
+            descent: base.descent.max(exponent.descent - raise),
+            primitives: Vec::new(),
+        };
+        let x = base.width;
+        power.place(base, Vector::ZERO);
+        power.place(exponent, Vector::new(x, -raise));
+        power
+    }
+
+    /// `base` with `subscript`, already at script size, lowered to its lower right.
+    fn subscript(base: Layout, subscript: Layout, size: f32) -> Self {
+        let lower = 0.25 * size;
+        let mut result = Self {
+            width: base.width + subscript.width,
+            ascent: base.ascent.max(subscript.ascent - lower),
+            descent: base.descent.max(lower + subscript.descent),
+            primitives: Vec::new(),
+        };
+        let x = base.width;
+        result.place(base, Vector::ZERO);
+        result.place(subscript, Vector::new(x, lower));
+        result
+    }
+
+    /// A radical sign over `radicand`, with an optional `index` tucked into its crook.
+    fn radical(index: Option<Layout>, radicand: Layout, size: f32) -> Self {
+        let gap = 0.12 * size;
+        let top = -(radicand.ascent + gap);
+        let bottom = radicand.descent;
+        let height = bottom - top;
+        let shift = index
+            .as_ref()
+            .map_or(0.0, |index| (index.width - 0.2 * size).max(0.0));
+        let tick = shift + 0.5 * size;
+        let width = tick + radicand.width + 0.15 * size;
+
+        let crook = bottom - 0.5 * height;
+        let mut radical = Self {
+            width,
+            ascent: -top,
+            descent: bottom,
+            primitives: vec![Primitive::Polyline(vec![
+                Point::new(shift, crook + 0.05 * height),
+                Point::new(shift + 0.1 * size, crook),
+                Point::new(shift + 0.25 * size, bottom),
+                Point::new(tick, top),
+                Point::new(width, top),
+            ])],
+        };
+        if let Some(index) = index {
+            let baseline = crook - 0.05 * size - index.descent;
+            radical.ascent = radical.ascent.max(index.ascent - baseline);
+            radical.place(index, Vector::new(0.0, baseline));
+        }
+        radical.place(radicand, Vector::new(tick + 0.05 * size, 0.0));
+        radical
+    }
+
+    /// `inner` between a pair of delimiters stretched to its height.
+    fn delimited(inner: Layout, delimiter: Delimiter, size: f32) -> Self {
+        let margin = 0.05 * size;
+        let top = -(inner.ascent.max(ASCENT * size) + margin);
+        let bottom = inner.descent.max(DESCENT * size) + margin;
+        let width = 0.4 * size;
+        let right = width + inner.width;
+
+        let mut delimited = Self {
+            width: right + width,
+            ascent: -top,
+            descent: bottom,
+            primitives: vec![
+                delimiter.draw(0.0, width, top, bottom),
+                delimiter.draw(right + width, -width, top, bottom),
+            ],
+        };
+        delimited.place(inner, Vector::new(width, 0.0));
+        delimited
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delimiter {
+    Parenthesis,
+    Bar,
+    Floor,
+    Ceiling,
+}
+
+impl Delimiter {
+    /// Draws the left delimiter in a box starting at `x`; a negative `width` mirrors it.
+    fn draw(self, x: f32, width: f32, top: f32, bottom: f32) -> Primitive {
+        let inner = x + 0.75 * width;
+        let outer = x + 0.3 * width;
+        match self {
+            Delimiter::Parenthesis => Primitive::Curve {
+                from: Point::new(inner, top),
+                control: Point::new(x - 0.05 * width, 0.5 * (top + bottom)),
+                to: Point::new(inner, bottom),
+            },
+            Delimiter::Bar => Primitive::Polyline(vec![
+                Point::new(x + 0.5 * width, top),
+                Point::new(x + 0.5 * width, bottom),
+            ]),
+            Delimiter::Floor => Primitive::Polyline(vec![
+                Point::new(outer, top),
+                Point::new(outer, bottom),
+                Point::new(inner, bottom),
+            ]),
+            Delimiter::Ceiling => Primitive::Polyline(vec![
+                Point::new(inner, top),
+                Point::new(outer, top),
+                Point::new(outer, bottom),
+            ]),
+        }
+    }
+}
+
+/// How tightly an expression binds, to decide where parentheses are needed.
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Convert(..) => 0,
+        Expr::Binary(Operator::Add | Operator::Subtract, ..) | Expr::Uncertain(..) => 1,
+        Expr::Binary(Operator::Multiply, ..) => 2,
+        // A fraction bar groups by itself.
+        Expr::Binary(Operator::Divide, ..) | Expr::Negate(_) => 3,
+        Expr::Binary(Operator::Exponentiate, ..) => 5,
+        Expr::Factorial(_) => 6,
+        Expr::Number(_)
+        | Expr::Integer(_)
+        | Expr::Variable(_)
+        | Expr::Call(..)
+        | Expr::Date(_)
+        | Expr::Duration(_) => 7,
+    }
+}
+
+/// A variable, shown with its constant's symbol and subscript if it names one.
+fn variable(name: &str, size: f32) -> Layout {
+    let symbol = match constants::lookup(name) {
+        Some(constant) => constant.symbol,
+        None if name == "inf" => "∞",
+        None => name,
+    };
+    match symbol.split_once('_') {
+        Some((base, subscript)) if !base.is_empty() && !subscript.is_empty() => Layout::subscript(
+            Layout::text(base, size),
+            Layout::text(subscript, size * SCRIPT_SCALE),
+            size,
+        ),
+        _ => Layout::text(symbol, size),
+    }
+}
+
+struct Typesetter {
+    locale: Locale,
+}
+
+impl Typesetter {
+    fn typeset(&self, expr: &Expr, size: f32) -> Layout {
+        let operator = |symbol: &str| Layout::text(format!(" {symbol} "), size);
+        let operand = |expr: &Expr, tightest: u8| self.operand(expr, tightest, size);
+        match expr {
+            Expr::Number(n) => Layout::text(self.locale.display(&n.to_string()), size),
+            Expr::Integer(n) => Layout::text(self.locale.display(&n.to_string()), size),
+            Expr::Date(_) | Expr::Duration(_) => Layout::text(expr.to_string(), size),
+            Expr::Variable(name) => variable(name, size),
+            Expr::Negate(value) => Layout::row([Layout::text("−", size), operand(value, 3)]),
+            Expr::Factorial(value) => Layout::row([operand(value, 7), Layout::text("!", size)]),
+            Expr::Binary(Operator::Divide, numerator, denominator) => Layout::fraction(
+                self.typeset(numerator, size),
+                self.typeset(denominator, size),
+                size,
+            ),
+            Expr::Binary(Operator::Exponentiate, base, exponent) => Layout::power(
+                operand(base, 6),
+                self.typeset(exponent, size * SCRIPT_SCALE),
+                size,
+            ),
+            Expr::Binary(Operator::Multiply, lhs, rhs) => {
+                // `2π`, `5 km` and `3 sin(x)` are written side by side.
+                let juxtaposed = matches!(**lhs, Expr::Number(_) | Expr::Integer(_))
+                    && match &**rhs {
+                        Expr::Variable(_) | Expr::Call(..) => true,
+                        Expr::Binary(Operator::Exponentiate, base, _) => {
+                            matches!(**base, Expr::Variable(_))
+                        }
+                        _ => false,
+                    };
+                let between = if juxtaposed {
+                    Layout::space(0.2 * size)
+                } else {
+                    operator("·")
+                };
+                Layout::row([operand(lhs, 2), between, operand(rhs, 2)])
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                // Only the right operand of a subtraction needs parentheses around a sum.
+                let tightest = if *op == Operator::Subtract { 2 } else { 1 };
+                Layout::row([
+                    operand(lhs, 1),
+                    operator(op.symbol()),
+                    operand(rhs, tightest),
+                ])
+            }
+            Expr::Uncertain(value, uncertainty) => {
+                Layout::row([operand(value, 1), operator("±"), operand(uncertainty, 2)])
+            }
+            Expr::Convert(value, unit) => Layout::row([
+                self.typeset(value, size),
+                operator("→"),
+                self.typeset(unit, size),
+            ]),
+            Expr::Call(name, args) => self.call(name, args, size),
+        }
+    }
+
+    /// Typesets `expr`, in parentheses if it binds less tightly than `tightest`.
+    fn operand(&self, expr: &Expr, tightest: u8, size: f32) -> Layout {
+        let layout = self.typeset(expr, size);
+        if precedence(expr) < tightest {
+            Layout::delimited(layout, Delimiter::Parenthesis, size)
+        } else {
+            layout
+        }
+    }
+
+    fn call(&self, name: &str, args: &[Expr], size: f32) -> Layout {
+        let script = size * SCRIPT_SCALE;
+        let delimited =
+            |arg: &Expr, delimiter| Layout::delimited(self.typeset(arg, size), delimiter, size);
+        match (name, args) {
+            ("sqrt", [x]) => Layout::radical(None, self.typeset(x, size), size),
+            ("cbrt", [x]) => {
+                Layout::radical(Some(Layout::text("3", script)), self.typeset(x, size), size)
+            }
+            ("root", [x, degree]) => Layout::radical(
+                Some(self.typeset(degree, script)),
+                self.typeset(x, size),
+                size,
+            ),
+            ("log", [x]) => Layout::row([
+                Layout::subscript(Layout::text("log", size), Layout::text("10", script), size),
+                delimited(x, Delimiter::Parenthesis),
+            ]),
+            ("exp", [x]) => Layout::power(Layout::text("e", size), self.typeset(x, script), size),
+            ("abs", [x]) => delimited(x, Delimiter::Bar),
+            ("floor", [x]) => delimited(x, Delimiter::Floor),
+            ("ceil", [x]) => delimited(x, Delimiter::Ceiling),
+            _ => {
+                let separator = format!("{} ", self.locale.separator());
+                let mut inner = Vec::new();
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        inner.push(Layout::text(separator.as_str(), size));
+                    }
+                    inner.push(self.typeset(arg, size));
+                }
+                Layout::row([
+                    Layout::text(name, size),
+                    Layout::delimited(Layout::row(inner), Delimiter::Parenthesis, size),
+                ])
+            }
+        }
+    }
+
+    /// Both sides of an equation, or a single expression.
+    fn sides(&self, sides: &[Expr], size: f32) -> Layout {
+        let mut row = Vec::new();
+        for (i, side) in sides.iter().enumerate() {
+            if i > 0 {
+                row.push(Layout::text(" = ", size));
+            }
+            row.push(self.typeset(side, size));
+        }
+        Layout::row(row)
+    }
+}
+
+/// An expression, or both sides of an equation, typeset as it would be written by hand.
+#[derive(Debug, Clone)]
+pub struct Formula {
+    sides: Vec<Expr>,
+    locale: Locale,
+    size: f32,
+}
+
+impl Formula {
+    pub fn new(sides: Vec<Expr>, locale: Locale, size: f32) -> Self {
+        Self {
+            sides,
+            locale,
+            size,
+        }
+    }
+
+    fn layout(&self, size: f32) -> Layout {
+        Typesetter {
+            locale: self.locale,
+        }
+        .sides(&self.sides, size)
+    }
+
+    /// Height of the canvas needed to show the formula at full size.
+    pub fn height(&self) -> f32 {
+        let layout = self.layout(self.size);
+        layout.ascent + layout.descent + 2.0 * PADDING
+    }
+}
+
+impl<Message> canvas::Program<Message> for Formula {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut layout = self.layout(self.size);
+        let available = bounds.width - 2.0 * PADDING;
+        if layout.width > available {
+            let size = (self.size * available / layout.width).max(SMALLEST_SIZE);
+            layout = self.layout(size);
+        }
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        let color = theme.palette().text;
+        let stroke = Stroke::default()
+            .with_color(color)
+            .with_width((0.06 * self.size).max(1.0));
+        let height = layout.ascent + layout.descent;
+        let origin = Vector::new(PADDING, (bounds.height - height) / 2.0 + layout.ascent);
+
+        for primitive in layout.primitives {
+            match primitive.translate(origin) {
+                Primitive::Text {
+                    position,
+                    content,
+                    size,
+                } => frame.fill_text(Text {
+                    content,
+                    position: Point::new(position.x, position.y - ASCENT * size),
+                    color,
+                    size: size.into(),
+                    line_height: LineHeight::Relative(1.0),
+                    vertical_alignment: alignment::Vertical::Top,
+                    ..Text::default()
+                }),
+                Primitive::Polyline(points) => {
+                    let path = Path::new(|builder| {
+                        for (i, &point) in points.iter().enumerate() {
+                            if i == 0 {
+                                builder.move_to(point);
+                            } else {
+                                builder.line_to(point);
+                            }
+                        }
+                    });
+                    frame.stroke(&path, stroke);
+                }
+                Primitive::Curve { from, control, to } => {
+                    let path = Path::new(|builder| {
+                        builder.move_to(from);
+                        builder.quadratic_curve_to(control, to);
+                    });
+                    frame.stroke(&path, stroke);
+                }
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    const SIZE: f32 = 20.0;
+
+    fn layout(input: &str, locale: Locale) -> Layout {
+        let sides = input
+            .split('=')
+            .map(|side| expr::parse(side).unwrap())
+            .collect::<Vec<_>>();
+        Typesetter { locale }.sides(&sides, SIZE)
+    }
+
+    /// The text pieces in drawing order, with their baseline positions and sizes.
+    fn texts(layout: &Layout) -> Vec<(&str, Point, f32)> {
+        layout
+            .primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Text {
+                    position,
+                    content,
+                    size,
+                } => Some((content.as_str(), *position, *size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn contents(input: &str) -> Vec<String> {
+        texts(&layout(input, Locale::Plain))
+            .into_iter()
+            .map(|(content, ..)| content.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn fraction() {
+        let fraction = layout("1/2", Locale::Plain);
+        let bar = -AXIS * SIZE;
+        assert!(matches!(
+            &fraction.primitives[0],
+            Primitive::Polyline(points) if points.iter().all(|point| point.y == bar)
+        ));
+        let [(_, numerator, _), (_, denominator, _)] = texts(&fraction)[..] else {
+            panic!("expected two numbers");
+        };
+        assert!(numerator.y < bar && denominator.y > bar);
+        // Both are centred over a bar slightly wider than either.
+        assert_eq!(numerator.x, denominator.x);
+        assert!(fraction.ascent > ASCENT * SIZE && fraction.descent > DESCENT * SIZE);
+    }
+
+    #[test]
+    fn power() {
+        let power = layout("x^2", Locale::Plain);
+        let [(_, base, base_size), (_, exponent, exponent_size)] = texts(&power)[..] else {
+            panic!("expected a base and an exponent");
+        };
+        assert_eq!(base, Point::ORIGIN);
+        assert_eq!(base_size, SIZE);
+        assert_eq!(exponent_size, SIZE * SCRIPT_SCALE);
+        assert_eq!(exponent.x, GLYPH_WIDTH * SIZE);
+        assert!(exponent.y < 0.0);
+        assert_eq!(power.width, GLYPH_WIDTH * SIZE * (1.0 + SCRIPT_SCALE));
+    }
+
+    #[test]
+    fn roots() {
+        let root = layout("sqrt(2)", Locale::Plain);
+        assert!(matches!(&root.primitives[0], Primitive::Polyline(points) if points.len() == 5));
+        let [(_, radicand, _)] = texts(&root)[..] else {
+            panic!("expected the radicand alone");
+        };
+        assert!(radicand.x > 0.0 && radicand.y == 0.0);
+        // The radical rises above the radicand it covers.
+        assert!(root.ascent > ASCENT * SIZE);
+
+        let cube = layout("cbrt(8)", Locale::Plain);
+        let sizes: Vec<f32> = texts(&cube).iter().map(|&(_, _, size)| size).collect();
+        assert_eq!(sizes, [SIZE * SCRIPT_SCALE, SIZE]);
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(contents("2x"), ["2", "x"]);
+        assert_eq!(contents("2*3"), ["2", " · ", "3"]);
+        assert_eq!(contents("x = 1 - 2"), ["x", " = ", "1", " − ", "2"]);
+        let curves = |input: &str| {
+            layout(input, Locale::Plain)
+                .primitives
+                .iter()
+                .filter(|primitive| matches!(primitive, Primitive::Curve { .. }))
+                .count()
+        };
+        // Parentheses only where precedence needs them.
+        assert_eq!(curves("(1 + 2) * 3"), 2);
+        assert_eq!(curves("1 + 2 * 3"), 0);
+        assert_eq!(curves("1 - (2 - 3)"), 2);
+        assert_eq!(curves("(1 - 2) - 3"), 0);
+    }
+
+    #[test]
+    fn locale() {
+        let texts: Vec<String> = texts(&layout("max(1.5, 2)", Locale::German))
+            .into_iter()
+            .map(|(content, ..)| content.to_string())
+            .collect();
+        assert_eq!(texts, ["max", "1,5", "; ", "2"]);
+    }
+}
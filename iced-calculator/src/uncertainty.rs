@@ -0,0 +1,365 @@
+use crate::{AngleMode, LogFunction, Operator, TrigFunction};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_PI_2, LN_10, PI};
+use std::fmt;
+
+type Function = fn(f64) -> f64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    ContainsZero(Uncertain),
+    Domain {
+        function: &'static str,
+        value: Uncertain,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ContainsZero(value) => write!(
+                f,
+                "cannot divide by [{}, {}], which contains zero",
+                value.lower(),
+                value.upper()
+            ),
+            Error::Domain { function, value } => write!(
+                f,
+                "{function} is not defined on all of [{}, {}]",
+                value.lower(),
+                value.upper()
+            ),
+        }
+    }
+}
+
+/// How uncertainties combine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Propagation {
+    /// Linearised Gaussian propagation for independent measurements.
+    #[default]
+    FirstOrder,
+    /// Guaranteed bounds: the result holds every value the inputs allow.
+    Interval,
+}
+
+impl Propagation {
+    pub const ALL: [Propagation; 2] = [Propagation::FirstOrder, Propagation::Interval];
+}
+
+impl fmt::Display for Propagation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Propagation::FirstOrder => "First order",
+            Propagation::Interval => "Interval",
+        })
+    }
+}
+
+/// A measurement `value ± uncertainty`. In interval arithmetic, the midpoint and
+/// half-width of the interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncertain {
+    pub value: f64,
+    pub uncertainty: f64,
+}
+
+impl Uncertain {
+    pub fn new(value: f64, uncertainty: f64) -> Self {
+        Self {
+            value,
+            uncertainty: uncertainty.abs(),
+        }
+    }
+
+    pub fn exact(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+
+    fn from_bounds(lower: f64, upper: f64) -> Self {
+        Self::new(0.5 * (lower + upper), 0.5 * (upper - lower))
+    }
+
+    pub fn lower(&self) -> f64 {
+        self.value - self.uncertainty
+    }
+
+    pub fn upper(&self) -> f64 {
+        self.value + self.uncertainty
+    }
+
+    pub fn negate(self) -> Self {
+        Self::new(-self.value, self.uncertainty)
+    }
+
+    pub fn binary(
+        self,
+        op: Operator,
+        other: Uncertain,
+        propagation: Propagation,
+    ) -> Result<Uncertain, Error> {
+        let (a, b) = (self, other);
+        if propagation == Propagation::FirstOrder {
+            let value = op.apply(a.value, b.value);
+            // Partial derivatives with respect to each operand.
+            let (da, db) = match op {
+                Operator::Add => (1.0, 1.0),
+                Operator::Subtract => (1.0, -1.0),
+                Operator::Multiply => (b.value, a.value),
+                Operator::Divide => (1.0 / b.value, -a.value / (b.value * b.value)),
+                Operator::Exponentiate => (
+                    b.value * a.value.powf(b.value - 1.0),
+                    if b.uncertainty == 0.0 {
+                        0.0
+                    } else {
+                        value * a.value.ln()
+                    },
+                ),
+            };
+            return Ok(Self::new(
+                value,
+                (da * a.uncertainty).hypot(db * b.uncertainty),
+            ));
+        }
+
+        let corners = |f: fn(f64, f64) -> f64| {
+            let values = [
+                f(a.lower(), b.lower()),
+                f(a.lower(), b.upper()),
+                f(a.upper(), b.lower()),
+                f(a.upper(), b.upper()),
+            ];
+            Self::from_bounds(
+                values.iter().copied().fold(f64::INFINITY, f64::min),
+                values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            )
+        };
+        let contains_zero = |x: Uncertain| x.lower() <= 0.0 && x.upper() >= 0.0;
+        let result = match op {
+            Operator::Add => Self::from_bounds(a.lower() + b.lower(), a.upper() + b.upper()),
+            Operator::Subtract => Self::from_bounds(a.lower() - b.upper(), a.upper() - b.lower()),
+            Operator::Multiply => corners(|x, y| x * y),
+            Operator::Divide if contains_zero(b) => return Err(Error::ContainsZero(b)),
+            Operator::Divide => corners(|x, y| x / y),
+            Operator::Exponentiate if b.uncertainty == 0.0 && b.value.fract() == 0.0 => {
+                if b.value < 0.0 && contains_zero(a) {
+                    return Err(Error::ContainsZero(a));
+                }
+                let power = corners(f64::powf);
+                // Even powers reach their minimum at zero, between the endpoints.
+                if b.value % 2.0 == 0.0 && b.value > 0.0 && contains_zero(a) {
+                    Self::from_bounds(0.0, power.upper())
+                } else {
+                    power
+                }
+            }
+            Operator::Exponentiate if a.lower() <= 0.0 => {
+                return Err(Error::Domain {
+                    function: "a non-integer power",
+                    value: a,
+                })
+            }
+            Operator::Exponentiate => corners(f64::powf),
+        };
+        Ok(result)
+    }
+
+    pub fn trig(
+        self,
+        trig_fn: TrigFunction,
+        angle_mode: AngleMode,
+        propagation: Propagation,
+    ) -> Result<Uncertain, Error> {
+        let x = if angle_mode == AngleMode::Degrees {
+            Self::new(self.value.to_radians(), self.uncertainty.to_radians())
+        } else {
+            self
+        };
+        let (function, derivative): (Function, Function) = match trig_fn {
+            TrigFunction::Sine => (f64::sin, f64::cos),
+            TrigFunction::Cosine => (f64::cos, |x| -x.sin()),
+            TrigFunction::Tangent => (f64::tan, |x| 1.0 / (x.cos() * x.cos())),
+        };
+        if propagation == Propagation::FirstOrder {
+            return Ok(x.linear(function, derivative));
+        }
+
+        let (lower, upper) = (x.lower(), x.upper());
+        let (mut min, mut max) = sorted(function(lower), function(upper));
+        match trig_fn {
+            TrigFunction::Sine | TrigFunction::Cosine => {
+                // Where sine peaks; cosine is sine shifted left by π/2.
+                let peak = if matches!(trig_fn, TrigFunction::Sine) {
+                    FRAC_PI_2
+                } else {
+                    0.0
+                };
+                if contains_periodic(lower, upper, peak, 2.0 * PI) {
+                    max = 1.0;
+                }
+                if contains_periodic(lower, upper, peak + PI, 2.0 * PI) {
+                    min = -1.0;
+                }
+            }
+            TrigFunction::Tangent => {
+                if contains_periodic(lower, upper, FRAC_PI_2, PI) {
+                    return Err(Error::Domain {
+                        function: "tan",
+                        value: self,
+                    });
+                }
+            }
+        }
+        Ok(Self::from_bounds(min, max))
+    }
+
+    pub fn log(self, log_fn: LogFunction, propagation: Propagation) -> Result<Uncertain, Error> {
+        let (name, derivative): (&'static str, Function) = match log_fn {
+            LogFunction::Log10 => ("log", |x| 1.0 / (x * LN_10)),
+            LogFunction::Ln => ("ln", |x| 1.0 / x),
+        };
+        self.monotonic(
+            name,
+            |x| log_fn.apply(x),
+            derivative,
+            |x| x > 0.0,
+            propagation,
+        )
+    }
+
+    pub fn sqrt(self, propagation: Propagation) -> Result<Uncertain, Error> {
+        let derivative = |x: f64| 0.5 / x.sqrt();
+        self.monotonic("sqrt", f64::sqrt, derivative, |x| x >= 0.0, propagation)
+    }
+
+    pub fn cbrt(self, propagation: Propagation) -> Result<Uncertain, Error> {
+        let derivative = |x: f64| 1.0 / (3.0 * x.cbrt() * x.cbrt());
+        self.monotonic("cbrt", f64::cbrt, derivative, |_| true, propagation)
+    }
+
+    pub fn exp(self, propagation: Propagation) -> Result<Uncertain, Error> {
+        self.monotonic("exp", f64::exp, f64::exp, |_| true, propagation)
+    }
+
+    /// An increasing function, defined from the first `x` where `defined(x)` holds.
+    fn monotonic(
+        self,
+        name: &'static str,
+        function: impl Fn(f64) -> f64,
+        derivative: Function,
+        defined: fn(f64) -> bool,
+        propagation: Propagation,
+    ) -> Result<Uncertain, Error> {
+        if !defined(self.lower()) {
+            return Err(Error::Domain {
+                function: name,
+                value: self,
+            });
+        }
+        Ok(match propagation {
+            Propagation::FirstOrder => self.linear(function, derivative),
+            Propagation::Interval => {
+                Self::from_bounds(function(self.lower()), function(self.upper()))
+            }
+        })
+    }
+
+    fn linear(self, function: impl Fn(f64) -> f64, derivative: Function) -> Uncertain {
+        Self::new(
+            function(self.value),
+            derivative(self.value).abs() * self.uncertainty,
+        )
+    }
+}
+
+fn sorted(a: f64, b: f64) -> (f64, f64) {
+    (a.min(b), a.max(b))
+}
+
+/// Whether `offset + k·period` lies in `[lower, upper]` for some integer k.
+fn contains_periodic(lower: f64, upper: f64, offset: f64, period: f64) -> bool {
+    offset + ((lower - offset) / period).ceil() * period <= upper
+}
+
+impl fmt::Display for Uncertain {
+    /// Rounds the uncertainty to two significant figures and the value to match.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uncertainty == 0.0 || !self.uncertainty.is_finite() || !self.value.is_finite() {
+            return write!(f, "{} ± {}", self.value, self.uncertainty);
+        }
+        let decimals = 1 - self.uncertainty.log10().floor() as i32;
+        if decimals > 0 {
+            let decimals = decimals as usize;
+            write!(
+                f,
+                "{:.decimals$} ± {:.decimals$}",
+                self.value, self.uncertainty
+            )
+        } else {
+            let scale = 10f64.powi(-decimals);
+            let round = |x: f64| (x / scale).round() * scale;
+            write!(f, "{} ± {}", round(self.value), round(self.uncertainty))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_order() {
+        let a = Uncertain::new(3.0, 0.3);
+        let b = Uncertain::new(4.0, 0.4);
+        let sum = a.binary(Operator::Add, b, Propagation::FirstOrder).unwrap();
+        assert_eq!(sum.value, 7.0);
+        assert!((sum.uncertainty - 0.5).abs() < 1e-12);
+        let product = a
+            .binary(Operator::Multiply, b, Propagation::FirstOrder)
+            .unwrap();
+        assert!((product.uncertainty - 1.2f64.hypot(1.2)).abs() < 1e-12);
+        let root = Uncertain::new(4.0, 0.4)
+            .sqrt(Propagation::FirstOrder)
+            .unwrap();
+        assert_eq!(root.value, 2.0);
+        assert!((root.uncertainty - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn interval() {
+        let a = Uncertain::new(2.0, 1.0);
+        let b = Uncertain::new(-1.0, 2.0);
+        let product = a
+            .binary(Operator::Multiply, b, Propagation::Interval)
+            .unwrap();
+        assert_eq!((product.lower(), product.upper()), (-9.0, 3.0));
+        assert_eq!(
+            Uncertain::new(1.0, 1.0).binary(Operator::Divide, b, Propagation::Interval),
+            Err(Error::ContainsZero(b))
+        );
+        // The square of [-1, 2] bottoms out at zero, not at an endpoint.
+        let square = Uncertain::new(0.5, 1.5)
+            .binary(
+                Operator::Exponentiate,
+                Uncertain::exact(2.0),
+                Propagation::Interval,
+            )
+            .unwrap();
+        assert_eq!((square.lower(), square.upper()), (0.0, 4.0));
+        let sine = Uncertain::new(FRAC_PI_2, 0.5)
+            .trig(
+                TrigFunction::Sine,
+                AngleMode::Radians,
+                Propagation::Interval,
+            )
+            .unwrap();
+        assert_eq!(sine.upper(), 1.0);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Uncertain::new(9.812, 0.0234).to_string(), "9.812 ± 0.023");
+        assert_eq!(Uncertain::new(12345.0, 678.0).to_string(), "12350 ± 680");
+    }
+}
@@ -0,0 +1,853 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const BASE_SYMBOLS: [&str; 9] = ["m", "kg", "s", "A", "K", "mol", "cd", "bit", "¤"];
+
+/// Exponents of the SI base units, plus bits for data sizes and money.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimension([i8; 9]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0; 9]);
+    const LENGTH: Dimension = Dimension([1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    const AREA: Dimension = Dimension([2, 0, 0, 0, 0, 0, 0, 0, 0]);
+    const VOLUME: Dimension = Dimension([3, 0, 0, 0, 0, 0, 0, 0, 0]);
+    const MASS: Dimension = Dimension([0, 1, 0, 0, 0, 0, 0, 0, 0]);
+    pub const TIME: Dimension = Dimension([0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    const CURRENT: Dimension = Dimension([0, 0, 0, 1, 0, 0, 0, 0, 0]);
+    const TEMPERATURE: Dimension = Dimension([0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    const AMOUNT: Dimension = Dimension([0, 0, 0, 0, 0, 1, 0, 0, 0]);
+    const LUMINOSITY: Dimension = Dimension([0, 0, 0, 0, 0, 0, 1, 0, 0]);
+    const DATA: Dimension = Dimension([0, 0, 0, 0, 0, 0, 0, 1, 0]);
+    const FREQUENCY: Dimension = Dimension([0, 0, -1, 0, 0, 0, 0, 0, 0]);
+    const SPEED: Dimension = Dimension([1, 0, -1, 0, 0, 0, 0, 0, 0]);
+    const ACCELERATION: Dimension = Dimension([1, 0, -2, 0, 0, 0, 0, 0, 0]);
+    const FORCE: Dimension = Dimension([1, 1, -2, 0, 0, 0, 0, 0, 0]);
+    const PRESSURE: Dimension = Dimension([-1, 1, -2, 0, 0, 0, 0, 0, 0]);
+    const ENERGY: Dimension = Dimension([2, 1, -2, 0, 0, 0, 0, 0, 0]);
+    const POWER: Dimension = Dimension([2, 1, -3, 0, 0, 0, 0, 0, 0]);
+    const CHARGE: Dimension = Dimension([0, 0, 1, 1, 0, 0, 0, 0, 0]);
+    const VOLTAGE: Dimension = Dimension([2, 1, -3, -1, 0, 0, 0, 0, 0]);
+    const RESISTANCE: Dimension = Dimension([2, 1, -3, -2, 0, 0, 0, 0, 0]);
+    const DATA_RATE: Dimension = Dimension([0, 0, -1, 0, 0, 0, 0, 1, 0]);
+    pub const MONEY: Dimension = Dimension([0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    pub fn is_none(self) -> bool {
+        self == Dimension::NONE
+    }
+
+    fn combine(self, other: Dimension, sign: i8) -> Dimension {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(other.0) {
+            *exponent += sign * other;
+        }
+        Dimension(exponents)
+    }
+
+    pub fn multiply(self, other: Dimension) -> Dimension {
+        self.combine(other, 1)
+    }
+
+    pub fn divide(self, other: Dimension) -> Dimension {
+        self.combine(other, -1)
+    }
+
+    pub fn power(self, exponent: f64) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for base in &mut exponents {
+            let scaled = f64::from(*base) * exponent;
+            if scaled.fract() != 0.0 || scaled.abs() > f64::from(i8::MAX) {
+                return None;
+            }
+            *base = scaled as i8;
+        }
+        Some(Dimension(exponents))
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(unit) = UNITS
+            .iter()
+            .find(|unit| unit.named_si && unit.dimension == *self)
+        {
+            return write!(f, "{}", unit.names[0]);
+        }
+
+        let part = |exponent: i8, symbol: &str| match exponent {
+            1 => symbol.to_string(),
+            n => format!("{symbol}^{n}"),
+        };
+        let numerator: Vec<String> = BASE_SYMBOLS
+            .iter()
+            .zip(self.0)
+            .filter(|(_, exponent)| *exponent > 0)
+            .map(|(symbol, exponent)| part(exponent, symbol))
+            .collect();
+        let denominator: Vec<String> = BASE_SYMBOLS
+            .iter()
+            .zip(self.0)
+            .filter(|(_, exponent)| *exponent < 0)
+            .map(|(symbol, exponent)| part(-exponent, symbol))
+            .collect();
+
+        match (numerator.is_empty(), denominator.is_empty()) {
+            (true, true) => Ok(()),
+            (_, true) => write!(f, "{}", numerator.join("·")),
+            (true, _) => write!(f, "1/{}", denominator.join("·")),
+            _ => write!(f, "{}/{}", numerator.join("·"), denominator.join("·")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Incompatible {
+        operation: &'static str,
+        lhs: Dimension,
+        rhs: Dimension,
+    },
+    Power(Dimension, f64),
+    UnknownUnit(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |dimension: &Dimension| {
+            if dimension.is_none() {
+                "a plain number".to_string()
+            } else {
+                dimension.to_string()
+            }
+        };
+        match self {
+            Error::Incompatible {
+                operation,
+                lhs,
+                rhs,
+            } => write!(f, "cannot {operation} {} and {}", name(lhs), name(rhs)),
+            Error::Power(dimension, exponent) => {
+                write!(f, "cannot raise {dimension} to the power {exponent}")
+            }
+            Error::UnknownUnit(name) => write!(f, "unknown unit '{name}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    Length,
+    Area,
+    Volume,
+    Mass,
+    Time,
+    Speed,
+    Temperature,
+    Data,
+    Energy,
+    Power,
+    Pressure,
+    Force,
+}
+
+impl Category {
+    pub const ALL: [Category; 12] = [
+        Category::Length,
+        Category::Area,
+        Category::Volume,
+        Category::Mass,
+        Category::Time,
+        Category::Speed,
+        Category::Temperature,
+        Category::Data,
+        Category::Energy,
+        Category::Power,
+        Category::Pressure,
+        Category::Force,
+    ];
+
+    /// Names of the units offered for this category in the conversion panel.
+    pub fn units(self) -> Vec<String> {
+        UNITS
+            .iter()
+            .filter(|unit| unit.category == Some(self))
+            .map(|unit| unit.names[0].to_string())
+            .collect()
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefixes {
+    None,
+    Si,
+    /// SI prefixes plus Ki, Mi, Gi, … for data sizes.
+    Binary,
+}
+
+struct Definition {
+    names: &'static [&'static str],
+    factor: f64,
+    offset: f64,
+    dimension: Dimension,
+    prefixes: Prefixes,
+    category: Option<Category>,
+    /// Used to name results of this dimension that have no display unit.
+    named_si: bool,
+}
+
+const fn unit(
+    names: &'static [&'static str],
+    factor: f64,
+    dimension: Dimension,
+    category: Option<Category>,
+) -> Definition {
+    Definition {
+        names,
+        factor,
+        offset: 0.0,
+        dimension,
+        prefixes: Prefixes::None,
+        category,
+        named_si: false,
+    }
+}
+
+const fn si(
+    names: &'static [&'static str],
+    factor: f64,
+    dimension: Dimension,
+    category: Option<Category>,
+) -> Definition {
+    Definition {
+        prefixes: Prefixes::Si,
+        named_si: factor == 1.0,
+        ..unit(names, factor, dimension, category)
+    }
+}
+
+const fn offset(names: &'static [&'static str], factor: f64, offset: f64) -> Definition {
+    Definition {
+        offset,
+        ..unit(
+            names,
+            factor,
+            Dimension::TEMPERATURE,
+            Some(Category::Temperature),
+        )
+    }
+}
+
+const fn data(names: &'static [&'static str], factor: f64) -> Definition {
+    Definition {
+        prefixes: Prefixes::Binary,
+        named_si: factor == 1.0,
+        ..unit(names, factor, Dimension::DATA, Some(Category::Data))
+    }
+}
+
+const POUND: f64 = 0.453_592_37;
+const INCH: f64 = 0.0254;
+const GALLON: f64 = 3.785_411_784e-3;
+const STANDARD_GRAVITY: f64 = 9.806_65;
+
+const UNITS: &[Definition] = &[
+    // Length
+    si(
+        &["m", "metre", "meter"],
+        1.0,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(
+        &["in", "inch"],
+        INCH,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(
+        &["ft", "foot", "feet"],
+        12.0 * INCH,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(
+        &["yd", "yard"],
+        36.0 * INCH,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(
+        &["mi", "mile"],
+        1_609.344,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(&["nmi"], 1_852.0, Dimension::LENGTH, Some(Category::Length)),
+    unit(
+        &["au"],
+        149_597_870_700.0,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    unit(
+        &["ly"],
+        9.460_730_472_580_8e15,
+        Dimension::LENGTH,
+        Some(Category::Length),
+    ),
+    // Area
+    unit(
+        &["ha", "hectare"],
+        1e4,
+        Dimension::AREA,
+        Some(Category::Area),
+    ),
+    unit(
+        &["acre"],
+        4_046.856_422_4,
+        Dimension::AREA,
+        Some(Category::Area),
+    ),
+    unit(
+        &["sqft"],
+        144.0 * INCH * INCH,
+        Dimension::AREA,
+        Some(Category::Area),
+    ),
+    unit(&["sqm"], 1.0, Dimension::AREA, Some(Category::Area)),
+    unit(&["sqkm"], 1e6, Dimension::AREA, Some(Category::Area)),
+    unit(
+        &["sqmi"],
+        1_609.344 * 1_609.344,
+        Dimension::AREA,
+        Some(Category::Area),
+    ),
+    // Volume
+    si(
+        &["L", "l", "litre", "liter"],
+        1e-3,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["gal", "gallon"],
+        GALLON,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["qt", "quart"],
+        GALLON / 4.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["pt", "pint"],
+        GALLON / 8.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["cup"],
+        GALLON / 16.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["floz"],
+        GALLON / 128.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["tbsp"],
+        GALLON / 256.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    unit(
+        &["tsp"],
+        GALLON / 768.0,
+        Dimension::VOLUME,
+        Some(Category::Volume),
+    ),
+    // Mass
+    si(&["g", "gram"], 1e-3, Dimension::MASS, Some(Category::Mass)),
+    unit(&["t", "tonne"], 1e3, Dimension::MASS, Some(Category::Mass)),
+    unit(
+        &["lb", "lbs", "pound"],
+        POUND,
+        Dimension::MASS,
+        Some(Category::Mass),
+    ),
+    unit(
+        &["oz", "ounce"],
+        POUND / 16.0,
+        Dimension::MASS,
+        Some(Category::Mass),
+    ),
+    unit(
+        &["st", "stone"],
+        14.0 * POUND,
+        Dimension::MASS,
+        Some(Category::Mass),
+    ),
+    // Time
+    si(
+        &["s", "sec", "second"],
+        1.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    unit(
+        &["min", "minute"],
+        60.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    unit(
+        &["h", "hr", "hour"],
+        3_600.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    unit(
+        &["day", "days"],
+        86_400.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    unit(
+        &["week", "weeks"],
+        604_800.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    unit(
+        &["year", "yr"],
+        31_557_600.0,
+        Dimension::TIME,
+        Some(Category::Time),
+    ),
+    // Speed
+    unit(&["m/s"], 1.0, Dimension::SPEED, Some(Category::Speed)),
+    unit(
+        &["kph", "kmh"],
+        1.0 / 3.6,
+        Dimension::SPEED,
+        Some(Category::Speed),
+    ),
+    unit(&["mph"], 0.447_04, Dimension::SPEED, Some(Category::Speed)),
+    unit(
+        &["kn", "knot"],
+        1_852.0 / 3_600.0,
+        Dimension::SPEED,
+        Some(Category::Speed),
+    ),
+    unit(
+        &["fps"],
+        12.0 * INCH,
+        Dimension::SPEED,
+        Some(Category::Speed),
+    ),
+    // Acceleration
+    unit(&["gee"], STANDARD_GRAVITY, Dimension::ACCELERATION, None),
+    // Temperature
+    Definition {
+        named_si: true,
+        ..unit(
+            &["K", "kelvin"],
+            1.0,
+            Dimension::TEMPERATURE,
+            Some(Category::Temperature),
+        )
+    },
+    offset(&["°C", "degC", "celsius"], 1.0, 273.15),
+    offset(&["°F", "degF", "fahrenheit"], 5.0 / 9.0, 459.67 * 5.0 / 9.0),
+    offset(&["°R", "degR", "rankine"], 5.0 / 9.0, 0.0),
+    // Data
+    data(&["bit", "b"], 1.0),
+    data(&["B", "byte"], 8.0),
+    unit(&["bps"], 1.0, Dimension::DATA_RATE, None),
+    // Energy
+    si(
+        &["J", "joule"],
+        1.0,
+        Dimension::ENERGY,
+        Some(Category::Energy),
+    ),
+    si(&["Wh"], 3_600.0, Dimension::ENERGY, Some(Category::Energy)),
+    si(&["cal"], 4.184, Dimension::ENERGY, Some(Category::Energy)),
+    si(
+        &["eV"],
+        1.602_176_634e-19,
+        Dimension::ENERGY,
+        Some(Category::Energy),
+    ),
+    unit(
+        &["BTU"],
+        1_055.055_852_62,
+        Dimension::ENERGY,
+        Some(Category::Energy),
+    ),
+    // Power
+    si(&["W", "watt"], 1.0, Dimension::POWER, Some(Category::Power)),
+    unit(
+        &["hp"],
+        745.699_871_582_270_2,
+        Dimension::POWER,
+        Some(Category::Power),
+    ),
+    // Pressure
+    si(
+        &["Pa", "pascal"],
+        1.0,
+        Dimension::PRESSURE,
+        Some(Category::Pressure),
+    ),
+    si(&["bar"], 1e5, Dimension::PRESSURE, Some(Category::Pressure)),
+    unit(
+        &["atm"],
+        101_325.0,
+        Dimension::PRESSURE,
+        Some(Category::Pressure),
+    ),
+    unit(
+        &["psi"],
+        6_894.757_293_168,
+        Dimension::PRESSURE,
+        Some(Category::Pressure),
+    ),
+    unit(
+        &["mmHg"],
+        133.322_387_415,
+        Dimension::PRESSURE,
+        Some(Category::Pressure),
+    ),
+    // Force
+    si(
+        &["N", "newton"],
+        1.0,
+        Dimension::FORCE,
+        Some(Category::Force),
+    ),
+    unit(
+        &["lbf"],
+        POUND * STANDARD_GRAVITY,
+        Dimension::FORCE,
+        Some(Category::Force),
+    ),
+    unit(
+        &["kgf"],
+        STANDARD_GRAVITY,
+        Dimension::FORCE,
+        Some(Category::Force),
+    ),
+    // Electromagnetism and others
+    si(&["A", "amp", "ampere"], 1.0, Dimension::CURRENT, None),
+    si(&["C", "coulomb"], 1.0, Dimension::CHARGE, None),
+    si(&["V", "volt"], 1.0, Dimension::VOLTAGE, None),
+    si(&["Ω", "ohm"], 1.0, Dimension::RESISTANCE, None),
+    si(&["Hz", "hertz"], 1.0, Dimension::FREQUENCY, None),
+    si(&["mol", "mole"], 1.0, Dimension::AMOUNT, None),
+    si(&["cd", "candela"], 1.0, Dimension::LUMINOSITY, None),
+];
+
+const SI_PREFIXES: [(&str, f64); 20] = [
+    ("da", 1e1),
+    ("Q", 1e30),
+    ("R", 1e27),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+];
+
+const BINARY_PREFIXES: [(&str, f64); 6] = [
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("Ei", 1_152_921_504_606_846_976.0),
+];
+
+/// A named unit, possibly prefixed (`km`, `MiB`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub factor: f64,
+    /// Zero point in kelvin for temperature scales such as °C.
+    pub offset: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    pub fn lookup(name: &str) -> Option<Unit> {
+        let found = |definition: &Definition, prefix: f64| Unit {
+            name: name.to_string(),
+            factor: prefix * definition.factor,
+            offset: definition.offset,
+            dimension: definition.dimension,
+        };
+
+        if let Some(definition) = UNITS.iter().find(|unit| unit.names.contains(&name)) {
+            return Some(found(definition, 1.0));
+        }
+
+        for definition in UNITS.iter().filter(|unit| unit.prefixes != Prefixes::None) {
+            let prefixes = SI_PREFIXES
+                .iter()
+                .chain(if definition.prefixes == Prefixes::Binary {
+                    BINARY_PREFIXES.iter()
+                } else {
+                    [].iter()
+                });
+            for (prefix, scale) in prefixes {
+                if name
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| definition.names.contains(&rest))
+                {
+                    return Some(found(definition, *scale));
+                }
+            }
+        }
+        None
+    }
+
+    fn is_offset(&self) -> bool {
+        self.offset != 0.0
+    }
+}
+
+/// A value with a physical dimension, stored in SI base units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub dimension: Dimension,
+    /// The unit to show the value in; base SI units when `None`.
+    pub unit: Option<Unit>,
+}
+
+impl Quantity {
+    /// `amount` of `unit`, e.g. `20 °C` is 293.15 K.
+    pub fn new(amount: f64, unit: Unit) -> Self {
+        Self {
+            value: amount * unit.factor + unit.offset,
+            dimension: unit.dimension,
+            unit: Some(unit),
+        }
+    }
+
+    /// A value already in SI base units.
+    pub fn base(value: f64, dimension: Dimension) -> Self {
+        Self {
+            value,
+            dimension,
+            unit: None,
+        }
+    }
+
+    pub fn add(&self, other: &Quantity) -> Result<Quantity, Error> {
+        self.additive(other, "add", self.value + other.value)
+    }
+
+    pub fn subtract(&self, other: &Quantity) -> Result<Quantity, Error> {
+        self.additive(other, "subtract", self.value - other.value)
+    }
+
+    fn additive(
+        &self,
+        other: &Quantity,
+        operation: &'static str,
+        value: f64,
+    ) -> Result<Quantity, Error> {
+        if self.dimension != other.dimension {
+            return Err(Error::Incompatible {
+                operation,
+                lhs: self.dimension,
+                rhs: other.dimension,
+            });
+        }
+        // Sums and differences of °C or °F are intervals, so fall back to kelvin.
+        let unit = self.unit.clone().filter(|unit| !unit.is_offset());
+        Ok(Quantity {
+            value,
+            dimension: self.dimension,
+            unit,
+        })
+    }
+
+    pub fn multiply(&self, other: &Quantity) -> Quantity {
+        Quantity::base(
+            self.value * other.value,
+            self.dimension.multiply(other.dimension),
+        )
+    }
+
+    pub fn divide(&self, other: &Quantity) -> Quantity {
+        Quantity::base(
+            self.value / other.value,
+            self.dimension.divide(other.dimension),
+        )
+    }
+
+    /// Multiplies by a plain number, keeping the display unit.
+    pub fn scale(&self, factor: f64) -> Quantity {
+        match &self.unit {
+            Some(unit) if unit.is_offset() => Quantity::base(self.value * factor, self.dimension),
+            _ => Quantity {
+                value: self.value * factor,
+                ..self.clone()
+            },
+        }
+    }
+
+    pub fn power(&self, exponent: f64) -> Result<Quantity, Error> {
+        let dimension = self
+            .dimension
+            .power(exponent)
+            .ok_or(Error::Power(self.dimension, exponent))?;
+        Ok(Quantity::base(self.value.powf(exponent), dimension))
+    }
+
+    pub fn convert(&self, unit: Unit) -> Result<Quantity, Error> {
+        if self.dimension != unit.dimension {
+            return Err(Error::Incompatible {
+                operation: "convert between",
+                lhs: self.dimension,
+                rhs: unit.dimension,
+            });
+        }
+        Ok(Quantity {
+            unit: Some(unit),
+            ..self.clone()
+        })
+    }
+
+    /// The value expressed in the display unit.
+    pub fn amount(&self) -> f64 {
+        match &self.unit {
+            Some(unit) => (self.value - unit.offset) / unit.factor,
+            None => self.value,
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} {}", self.amount(), unit.name),
+            None => write!(f, "{} {}", self.value, self.dimension),
+        }
+    }
+}
+
+/// Converts `amount` between two named units for the conversion panel.
+pub fn convert(amount: f64, from: &str, to: &str) -> Result<Quantity, Error> {
+    let lookup =
+        |name: &str| Unit::lookup(name).ok_or_else(|| Error::UnknownUnit(name.to_string()));
+    Quantity::new(amount, lookup(from)?).convert(lookup(to)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{evaluate, Context, Value};
+
+    fn quantity(input: &str) -> Quantity {
+        match evaluate(input, &Context::default()) {
+            Ok(Value::Quantity(quantity)) => quantity,
+            other => panic!("{input} gave {other:?}"),
+        }
+    }
+
+    #[test]
+    fn compound_conversion() {
+        let speed = quantity("5 km / 20 min to mph");
+        assert_eq!(
+            speed.unit.as_ref().map(|unit| unit.name.as_str()),
+            Some("mph")
+        );
+        assert!((speed.amount() - 15.0 / 1.609_344).abs() < 1e-12);
+        assert_eq!(speed.dimension, Dimension::SPEED);
+    }
+
+    #[test]
+    fn offset_temperatures() {
+        assert!((quantity("20 °C to °F").amount() - 68.0).abs() < 1e-12);
+        assert!((convert(0.0, "K", "°C").unwrap().amount() + 273.15).abs() < 1e-12);
+        // A difference of temperatures is an interval, shown in kelvin.
+        let difference = quantity("30 °C - 20 °C");
+        assert_eq!(difference.unit, None);
+        assert!((difference.value - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn incompatible_dimensions() {
+        assert!(matches!(
+            convert(1.0, "kg", "m"),
+            Err(Error::Incompatible { .. })
+        ));
+        let metre = Quantity::new(1.0, Unit::lookup("m").unwrap());
+        let second = Quantity::new(1.0, Unit::lookup("s").unwrap());
+        assert_eq!(
+            metre.add(&second),
+            Err(Error::Incompatible {
+                operation: "add",
+                lhs: Dimension::LENGTH,
+                rhs: Dimension::TIME,
+            })
+        );
+        assert_eq!(
+            convert(1.0, "furlongs", "m"),
+            Err(Error::UnknownUnit("furlongs".into()))
+        );
+    }
+
+    #[test]
+    fn prefixes_and_names() {
+        let factor = |name: &str| Unit::lookup(name).map(|unit| unit.factor);
+        // A bare prefix is not a unit, and whole names win over prefixed ones.
+        assert_eq!(factor("c"), None);
+        assert_eq!(factor("cm"), Some(1e-2));
+        assert_eq!(Unit::lookup("cd").unwrap().dimension, Dimension::LUMINOSITY);
+        assert_eq!(factor("h"), Some(3600.0));
+        assert_eq!(factor("hm"), Some(100.0));
+        assert_eq!(factor("min"), Some(60.0));
+        assert_eq!(factor("KiB"), Some(1024.0 * 8.0));
+        assert_eq!(factor("Kim"), None);
+        assert!((quantity("2 h to min").amount() - 120.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dimension_arithmetic() {
+        assert_eq!(Dimension::LENGTH.divide(Dimension::TIME), Dimension::SPEED);
+        assert_eq!(
+            Dimension::LENGTH.multiply(Dimension::LENGTH).power(0.5),
+            Some(Dimension::LENGTH)
+        );
+        assert_eq!(Dimension::LENGTH.power(0.5), None);
+        assert_eq!(Dimension::SPEED.to_string(), "m/s");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::expr::{self, Context};
+use crate::locale::Locale;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Extension worksheets are saved with.
+pub const EXTENSION: &str = "calc";
+
+/// Name of the previous line's result.
+const PREVIOUS: &str = "ans";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Io(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Evaluates every line of `sheet`, returning one result per line.
+///
+/// Blank lines and `#` comments give an empty result. `name = expression` assigns a
+/// variable, and each result is also available as `line1`, `line2`, … and `ans`.
+/// Lines are read with the separators of `locale`.
+pub fn evaluate(sheet: &str, context: &Context, locale: Locale) -> Vec<String> {
+    let mut context = context.clone();
+    let mut results = Vec::new();
+
+    for (index, line) in sheet.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            results.push(String::new());
+            continue;
+        }
+
+        let (name, expression) = match line.split_once('=') {
+            Some((name, expression)) if is_identifier(name.trim()) => {
+                (Some(name.trim()), expression)
+            }
+            _ => (None, line),
+        };
+        let value = locale
+            .delocalize(expression)
+            .map_err(expr::Error::from)
+            .and_then(|expression| expr::evaluate(&expression, &context));
+        context.take_notes();
+        results.push(match value {
+            Ok(value) => {
+                let result = value.to_string();
+                if let Some(name) = name {
+                    context.set(name, value.clone());
+                }
+                context.set(&format!("line{}", index + 1), value.clone());
+                context.set(PREVIOUS, value);
+                result
+            }
+            Err(error) => format!("⚠ {error}"),
+        });
+    }
+    results
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Adds the `.calc` extension when the path has none.
+pub fn with_extension(path: &str) -> PathBuf {
+    let path = PathBuf::from(path.trim());
+    if path.extension().is_some() {
+        path
+    } else {
+        path.with_extension(EXTENSION)
+    }
+}
+
+pub fn load(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path)
+        .map_err(|error| Error::Io(format!("cannot read {}: {error}", path.display())))
+}
+
+pub fn save(path: &Path, sheet: &str) -> Result<(), Error> {
+    std::fs::write(path, sheet)
+        .map_err(|error| Error::Io(format!("cannot write {}: {error}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_refer_to_earlier_results() {
+        let sheet = "price = 120\n# a comment\nprice * 2\nans + line1\n\nbad +";
+        let results = evaluate(sheet, &Context::default(), Locale::default());
+        assert_eq!(results[..5], ["120", "", "240", "360", ""]);
+        assert!(results[5].starts_with('⚠'));
+    }
+
+    #[test]
+    fn lines_use_the_locale() {
+        let results = evaluate("x = 1,5\nx * 2", &Context::default(), Locale::German);
+        assert_eq!(results, ["1.5", "3"]);
+    }
+
+    #[test]
+    fn extension() {
+        assert_eq!(with_extension("budget"), PathBuf::from("budget.calc"));
+        assert_eq!(with_extension("budget.txt"), PathBuf::from("budget.txt"));
+    }
+}
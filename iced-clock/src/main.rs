@@ -1,155 +1,426 @@
 use chrono::{DateTime, Local, Timelike};
+use iced::futures::{stream, Stream};
 use iced::{
-    mouse, time,
+    alignment, mouse,
     widget::{
         canvas::{self, Geometry, Path, Stroke},
-        Canvas,
+        column, pick_list, row, text, Canvas,
     },
-    Color, Element, Fill, Pixels, Point, Rectangle, Renderer, Subscription, Task, Theme,
+    window, Center, Color, Element, Fill, Pixels, Point, Rectangle, Renderer, Subscription, Task,
+    Theme, Vector,
 };
+use std::fmt;
+use std::time::Duration;
 
 fn main() -> iced::Result {
     iced::application("Clock - Iced", Clock::update, Clock::view)
         .subscription(Clock::subscription)
         .antialiasing(true)
-        .theme(|_| Theme::Dark)
+        .theme(Clock::theme)
         .run_with(Clock::new)
 }
 
 struct Clock {
     time: DateTime<Local>,
+    hands: Hands,
+    motion: Motion,
+    theme: Theme,
+    /// The background, ticks and numerals, which only change with the window size
+    /// and the theme. The cache redraws by itself when the size changes.
+    dial: canvas::Cache,
 }
 
-struct ClockFace {
+struct ClockFace<'a> {
     time: DateTime<Local>,
+    hands: Hands,
+    motion: Motion,
+    dial: &'a canvas::Cache,
 }
 
+/// How the second hand moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Motion {
+    /// One jump per second, right on the second.
+    #[default]
+    Quartz,
+    /// Many small beats per second, like a mechanical movement.
+    Sweep,
+    /// One jump per second that overshoots and settles back.
+    Bounce,
+}
+
+// Dial dimensions as fractions of the radius, so the clock looks the same at any size.
+/// Where the ticks end, short of the rim.
+const TICK_END: f32 = 0.93;
+/// Where hour ticks start, and their width.
+const HOUR_TICK: (f32, f32) = (0.86, 0.013);
+/// Where minute ticks start, and their width.
+const MINUTE_TICK: (f32, f32) = (0.9, 0.007);
+/// Distance of the numerals' centers from the center of the dial.
+const NUMERAL_DISTANCE: f32 = 0.74;
+const NUMERAL_SIZE: f32 = 0.13;
+const RIM_WIDTH: f32 = 0.025;
+
+/// Beats per second of the sweep, as in a 28,800 bph movement.
+const BEATS_PER_SECOND: f32 = 8.0;
+/// How long the bounce takes to settle, in seconds.
+const BOUNCE_DURATION: f32 = 0.2;
+/// How far the bounce overshoots; 1.70158 gives the usual 10%.
+const BOUNCE_OVERSHOOT: f32 = 1.70158;
+
+impl Motion {
+    const ALL: [Motion; 3] = [Motion::Quartz, Motion::Sweep, Motion::Bounce];
+
+    /// The second hand's position in seconds past the minute, from the exact
+    /// seconds past the minute.
+    fn seconds(self, exact: f32) -> f32 {
+        let whole = exact.floor();
+        match self {
+            Motion::Quartz => whole,
+            Motion::Sweep => (exact * BEATS_PER_SECOND).floor() / BEATS_PER_SECOND,
+            Motion::Bounce => {
+                // Ease out with overshoot from the previous second to this one.
+                let t = ((exact - whole) / BOUNCE_DURATION).min(1.0) - 1.0;
+                let eased =
+                    1.0 + (BOUNCE_OVERSHOOT + 1.0) * t.powi(3) + BOUNCE_OVERSHOOT * t.powi(2);
+                whole - 1.0 + eased
+            }
+        }
+    }
+}
+
+impl fmt::Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Motion::Quartz => "Quartz tick",
+            Motion::Sweep => "Mechanical sweep",
+            Motion::Bounce => "Tick with bounce",
+        })
+    }
+}
+
+/// How a hand is drawn. Lengths and widths are fractions of the dial's radius.
 #[derive(Debug, Clone, Copy)]
+struct HandStyle {
+    /// From the center to the tip.
+    length: f32,
+    /// How far the hand reaches back past the center.
+    tail: f32,
+    /// Width at the center, narrowing to `tip_width` at the tip.
+    base_width: f32,
+    tip_width: f32,
+    /// Radius of a round counterweight at the end of the tail.
+    counterweight: Option<f32>,
+    /// The theme's text color, like the numerals, when `None`.
+    color: Option<Color>,
+    shadow: bool,
+}
+
+/// The look of all three hands and the cap that covers where they meet.
+#[derive(Debug, Clone, Copy)]
+struct Hands {
+    hour: HandStyle,
+    minute: HandStyle,
+    second: HandStyle,
+    cap_radius: f32,
+    cap_color: Color,
+    /// Offset of the drop shadows, as a fraction of the radius; light comes from the top left.
+    shadow_offset: Vector,
+    shadow_color: Color,
+}
+
+impl Default for Hands {
+    fn default() -> Self {
+        Self {
+            hour: HandStyle {
+                length: 0.5,
+                tail: 0.1,
+                base_width: 0.06,
+                tip_width: 0.025,
+                counterweight: None,
+                color: None,
+                shadow: true,
+            },
+            minute: HandStyle {
+                length: 0.75,
+                tail: 0.1,
+                base_width: 0.045,
+                tip_width: 0.015,
+                counterweight: None,
+                color: None,
+                shadow: true,
+            },
+            second: HandStyle {
+                length: 0.88,
+                tail: 0.22,
+                base_width: 0.016,
+                tip_width: 0.006,
+                counterweight: Some(0.035),
+                color: Some(Color::from_rgb8(200, 30, 30)),
+                shadow: true,
+            },
+            cap_radius: 0.04,
+            cap_color: Color::from_rgb8(200, 30, 30),
+            shadow_offset: Vector::new(0.012, 0.018),
+            shadow_color: Color::from_rgba8(0, 0, 0, 0.25),
+        }
+    }
+}
+
+impl HandStyle {
+    /// The hand pointing straight up from the origin, at `radius` scale.
+    fn path(&self, radius: f32) -> Path {
+        let base = self.base_width * radius / 2.0;
+        let tip = self.tip_width * radius / 2.0;
+        let tail = self.tail * radius;
+        let length = self.length * radius;
+        Path::new(|builder| {
+            builder.move_to(Point::new(-base, tail));
+            builder.line_to(Point::new(-tip, -length));
+            builder.line_to(Point::new(tip, -length));
+            builder.line_to(Point::new(base, tail));
+            builder.close();
+            if let Some(counterweight) = self.counterweight {
+                builder.circle(Point::new(0.0, tail), counterweight * radius);
+            }
+        })
+    }
+
+    /// Draws the hand at `angle` radians clockwise from twelve, with its shadow.
+    fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        center: Point,
+        radius: f32,
+        angle: f32,
+        hands: &Hands,
+        ink: Color,
+    ) {
+        let path = self.path(radius);
+        if self.shadow {
+            frame.with_save(|frame| {
+                frame.translate(Vector::new(center.x, center.y) + hands.shadow_offset * radius);
+                frame.rotate(angle);
+                frame.fill(&path, hands.shadow_color);
+            });
+        }
+        frame.with_save(|frame| {
+            frame.translate(Vector::new(center.x, center.y));
+            frame.rotate(angle);
+            frame.fill(&path, self.color.unwrap_or(ink));
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
 enum Message {
     Tick(DateTime<Local>),
+    MotionSelected(Motion),
+    ThemeSelected(Theme),
 }
 
 impl Clock {
     fn new() -> (Self, Task<Message>) {
-        (Self { time: Local::now() }, Task::none())
+        (
+            Self {
+                time: Local::now(),
+                hands: Hands::default(),
+                motion: Motion::default(),
+                theme: Theme::Dark,
+                dial: canvas::Cache::new(),
+            },
+            Task::none(),
+        )
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Tick(local_time) => self.time = local_time,
+            Message::MotionSelected(motion) => self.motion = motion,
+            Message::ThemeSelected(theme) => {
+                self.theme = theme;
+                self.dial.clear();
+            }
         }
         Task::none()
     }
 
-    fn view(&self) -> Element<Message> {
-        Canvas::new(ClockFace { time: self.time })
-            .width(Fill)
-            .height(Fill)
-            .into()
+    fn view(&self) -> Element<'_, Message> {
+        let controls = row![
+            text("Second hand"),
+            pick_list(Motion::ALL, Some(self.motion), Message::MotionSelected),
+            text("Theme"),
+            pick_list(Theme::ALL, Some(self.theme.clone()), Message::ThemeSelected),
+        ]
+        .spacing(10)
+        .align_y(Center);
+        let face = Canvas::new(ClockFace {
+            time: self.time,
+            hands: self.hands,
+            motion: self.motion,
+            dial: &self.dial,
+        })
+        .width(Fill)
+        .height(Fill);
+        column![controls, face].spacing(10).padding(10).into()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme.clone()
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        time::every(std::time::Duration::from_millis(500)).map(|_| Message::Tick(Local::now()))
+        match self.motion {
+            Motion::Quartz => Subscription::run(quartz_ticks),
+            Motion::Sweep | Motion::Bounce => window::frames().map(|_| Message::Tick(Local::now())),
+        }
     }
 }
 
-impl<Message> canvas::Program<Message> for ClockFace {
+/// One tick just after each second starts, sleeping through the rest of the current
+/// one, so the hand jumps on time without waking up in between.
+fn quartz_ticks() -> impl Stream<Item = Message> {
+    stream::unfold((), |()| async {
+        let left = 1_000_000_000 - Local::now().nanosecond().min(999_999_999);
+        tokio::time::sleep(Duration::from_nanos(left.into())).await;
+        Some((Message::Tick(Local::now()), ()))
+    })
+}
+
+impl<Message> canvas::Program<Message> for ClockFace<'_> {
     type State = ();
 
     fn draw(
         &self,
         _state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
-        let mut clock = canvas::Frame::new(renderer, bounds.size());
-
         let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
         let radius = bounds.width.min(bounds.height) / 2.0;
 
-        let background = Path::circle(center, radius);
-        clock.fill(&background, Color::from_rgb8(255, 255, 255));
+        // The dial follows the theme, so the cache is cleared when it changes.
+        let palette = theme.extended_palette();
+        let face = palette.background.base.color;
+        let ink = palette.background.base.text;
 
-        for hour in 1..=12 {
-            let angle = (hour as f32 * 30.0).to_radians();
-            let start = Point::new(
-                center.x + (radius - 20.0) * angle.sin(),
-                center.y - (radius - 20.0) * angle.cos(),
-            );
-            let end = Point::new(
-                center.x + (radius - 10.0) * angle.sin(),
-                center.y - (radius - 10.0) * angle.cos(),
-            );
-            let line = Path::line(start, end);
-            clock.stroke(&line, Stroke::default().with_width(2.0));
-
-            // Draw hour numbers
-            let text = format!("{}", hour);
-            let text_position = Point::new(
-                center.x + (radius - 40.0) * angle.sin() - 5.0,
-                center.y - (radius - 40.0) * angle.cos() + 5.0,
-            );
-            clock.fill_text(canvas::Text {
-                content: text,
-                position: text_position,
-                color: Color::BLACK,
-                size: Pixels { 0: 20.0 },
-                ..canvas::Text::default()
-            });
-        }
+        let dial = self.dial.draw(renderer, bounds.size(), |frame| {
+            let background = Path::circle(center, radius);
+            frame.fill(&background, face);
 
-        for minute in 0..60 {
-            if minute % 5 != 0 {
-                let angle = (minute as f32 * 6.0).to_radians();
-                let start = Point::new(
-                    center.x + (radius - 15.0) * angle.sin(),
-                    center.y - (radius - 15.0) * angle.cos(),
-                );
-                let end = Point::new(
-                    center.x + (radius - 10.0) * angle.sin(),
-                    center.y - (radius - 10.0) * angle.cos(),
+            let on_dial = |distance: f32, angle: f32| {
+                Point::new(
+                    center.x + distance * radius * angle.sin(),
+                    center.y - distance * radius * angle.cos(),
+                )
+            };
+
+            for hour in 1..=12 {
+                let angle = (hour as f32 * 30.0).to_radians();
+                let line = Path::line(on_dial(HOUR_TICK.0, angle), on_dial(TICK_END, angle));
+                frame.stroke(
+                    &line,
+                    Stroke::default()
+                        .with_width(HOUR_TICK.1 * radius)
+                        .with_color(ink),
                 );
-                let line = Path::line(start, end);
-                clock.stroke(&line, Stroke::default().with_width(1.0));
+
+                // Centered on its position, so one- and two-digit numbers line up.
+                frame.fill_text(canvas::Text {
+                    content: hour.to_string(),
+                    position: on_dial(NUMERAL_DISTANCE, angle),
+                    color: ink,
+                    size: Pixels(NUMERAL_SIZE * radius),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..canvas::Text::default()
+                });
+            }
+
+            for minute in 0..60 {
+                if minute % 5 != 0 {
+                    let angle = (minute as f32 * 6.0).to_radians();
+                    let line = Path::line(on_dial(MINUTE_TICK.0, angle), on_dial(TICK_END, angle));
+                    frame.stroke(
+                        &line,
+                        Stroke::default()
+                            .with_width(MINUTE_TICK.1 * radius)
+                            .with_color(ink),
+                    );
+                }
             }
-        }
 
+            frame.stroke(
+                &Path::circle(center, radius * (1.0 - RIM_WIDTH / 2.0)),
+                Stroke::default()
+                    .with_width(RIM_WIDTH * radius)
+                    .with_color(palette.primary.strong.color),
+            );
+        });
+
+        let mut clock = canvas::Frame::new(renderer, bounds.size());
+
+        // Past a leap second the nanoseconds run over 1e9; hold the hand at :59.
+        let nanoseconds = self.time.nanosecond().min(999_999_999);
+        let seconds = self
+            .motion
+            .seconds(self.time.second() as f32 + nanoseconds as f32 / 1e9);
         let hour_angle =
             (self.time.hour() as f32 * 30.0 + self.time.minute() as f32 * 0.5).to_radians();
-        let hour_hand = Path::line(
-            center,
-            Point::new(
-                center.x + (radius * 0.5) * hour_angle.sin(),
-                center.y - (radius * 0.5) * hour_angle.cos(),
-            ),
-        );
-        clock.stroke(&hour_hand, Stroke::default().with_width(4.0));
-
         let minute_angle = (self.time.minute() as f32 * 6.0).to_radians();
-        let minute_hand = Path::line(
-            center,
-            Point::new(
-                center.x + (radius * 0.7) * minute_angle.sin(),
-                center.y - (radius * 0.7) * minute_angle.cos(),
-            ),
-        );
-        clock.stroke(&minute_hand, Stroke::default().with_width(3.0));
-
-        let second_angle = (self.time.second() as f32 * 6.0).to_radians();
-        let second_hand = Path::line(
-            center,
-            Point::new(
-                center.x + (radius * 0.9) * second_angle.sin(),
-                center.y - (radius * 0.9) * second_angle.cos(),
-            ),
-        );
-        clock.stroke(
-            &second_hand,
-            Stroke::default().with_width(1.0).with_color(Color::BLACK),
+        let second_angle = (seconds * 6.0).to_radians();
+        let hands = &self.hands;
+        hands
+            .hour
+            .draw(&mut clock, center, radius, hour_angle, hands, ink);
+        hands
+            .minute
+            .draw(&mut clock, center, radius, minute_angle, hands, ink);
+        hands
+            .second
+            .draw(&mut clock, center, radius, second_angle, hands, ink);
+        clock.fill(
+            &Path::circle(center, hands.cap_radius * radius),
+            hands.cap_color,
         );
 
-        vec![clock.into_geometry()]
+        vec![dial, clock.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quartz_and_sweep() {
+        assert_eq!(Motion::Quartz.seconds(12.0), 12.0);
+        assert_eq!(Motion::Quartz.seconds(12.7), 12.0);
+        assert_eq!(Motion::Sweep.seconds(12.0), 12.0);
+        assert_eq!(Motion::Sweep.seconds(12.5625), 12.5);
+        assert_eq!(Motion::Sweep.seconds(12.99), 12.875);
+    }
+
+    #[test]
+    fn bounce() {
+        // Leaves the previous second, overshoots, then settles on the whole second.
+        assert_eq!(Motion::Bounce.seconds(12.0), 11.0);
+        let peak = (1..20)
+            .map(|step| Motion::Bounce.seconds(12.0 + step as f32 * BOUNCE_DURATION / 20.0))
+            .fold(f32::MIN, f32::max);
+        assert!(peak > 12.05 && peak < 12.15, "{peak}");
+        assert_eq!(Motion::Bounce.seconds(12.0 + BOUNCE_DURATION), 12.0);
+        assert_eq!(Motion::Bounce.seconds(12.5), 12.0);
+        assert_eq!(Motion::Bounce.seconds(59.99), 59.0);
+    }
+
+    #[test]
+    fn bounce_past_the_minute() {
+        // Just after :00 the hand is still coming from :59, before twelve.
+        assert_eq!(Motion::Bounce.seconds(0.0), -1.0);
+        assert!(Motion::Bounce.seconds(0.02) < 0.0);
+        assert_eq!(Motion::Bounce.seconds(0.5), 0.0);
     }
 }